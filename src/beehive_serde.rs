@@ -1,12 +1,27 @@
 use rand::{seq::SliceRandom, thread_rng};
 use serde::{Deserialize, Serialize};
 
-use crate::beehive_swap::BeehiveSwap;
+use crate::beehive_swap::{layout_locked_cells, BeehiveSwap};
+use crate::board::{Axes, Board, Cell};
+use crate::difficulty::{Difficulty, Rating};
+use crate::grid::Grid;
 use crate::grid_beehive::GridBeehive;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BeehiveSerde {
     layout: Vec<String>,
+    // optional hand-picked scramble, reshuffled at random when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scrambled: Option<Vec<String>>,
+    // cells that can't be swapped, the stock ones when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    locked: Option<Vec<Cell>>,
+    // filled by `beehive rate --write`, goes with the scramble it was rated on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rating: Option<Rating>,
+    // the line directions words are read along, all three when absent
+    #[serde(default, skip_serializing_if = "Axes::is_all")]
+    axes: Axes,
 }
 
 impl From<GridBeehive> for BeehiveSerde {
@@ -17,42 +32,156 @@ impl From<GridBeehive> for BeehiveSerde {
             layout.push(value.get_row(r).unwrap());
         }
 
-        Self { layout }
+        Self {
+            layout,
+            scrambled: None,
+            locked: None,
+            rating: None,
+            axes: value.axes(),
+        }
+    }
+}
+
+// square grids travel as beehives reading rows and cols only
+impl From<Grid> for BeehiveSerde {
+    fn from(value: Grid) -> Self {
+        Self::new(value.to_rows(), None, None).with_axes(value.axes())
     }
 }
 
+impl From<BeehiveSwap> for BeehiveSerde {
+    fn from(value: BeehiveSwap) -> Self {
+        let mut layout = vec![];
+        let mut scrambled = vec![];
+        for r in 0..value.rows() {
+            layout.push(value.get_solved_row(r).unwrap());
+            scrambled.push(value.get_shuffled_row(r).unwrap());
+        }
+
+        Self {
+            layout,
+            scrambled: Some(scrambled),
+            locked: Some(value.get_locked_cells().clone()),
+            rating: None,
+            axes: value.axes(),
+        }
+    }
+}
+
+impl BeehiveSerde {
+    pub fn new(
+        layout: Vec<String>,
+        scrambled: Option<Vec<String>>,
+        locked: Option<Vec<Cell>>,
+    ) -> Self {
+        Self {
+            layout,
+            scrambled,
+            locked,
+            rating: None,
+            axes: Axes::ALL,
+        }
+    }
+    pub fn with_axes(mut self, axes: Axes) -> Self {
+        self.axes = axes;
+        self
+    }
+    pub fn with_rating(mut self, rating: Rating) -> Self {
+        self.rating = Some(rating);
+        self
+    }
+    pub fn layout(&self) -> &Vec<String> {
+        &self.layout
+    }
+    pub fn locked(&self) -> Option<&Vec<Cell>> {
+        self.locked.as_ref()
+    }
+    pub fn axes(&self) -> Axes {
+        self.axes
+    }
+    pub fn rating(&self) -> Option<&Rating> {
+        self.rating.as_ref()
+    }
+    pub fn scrambled_rows(&self) -> Option<&Vec<String>> {
+        self.scrambled.as_ref()
+    }
+    pub fn scrambled(&self) -> Option<GridBeehive> {
+        self.scrambled
+            .as_ref()
+            .map(|scrambled| to_grid_beehive(scrambled).with_axes(self.axes))
+    }
+}
+
+fn to_grid_beehive(layout: &[String]) -> GridBeehive {
+    let rows = layout.len();
+    let cols = layout.first().map(|row| row.chars().count()).unwrap_or(0);
+
+    let mut beehive = GridBeehive::new(rows, cols);
+    for (row, val) in layout.iter().enumerate() {
+        beehive.set_row(row, val.clone());
+    }
+
+    beehive
+}
+
 impl Into<GridBeehive> for BeehiveSerde {
     fn into(self) -> GridBeehive {
-        let rows = self.layout.len();
-        let cols = match rows {
-            0 => 0,
-            _ => self.layout.get(0).unwrap().len(),
-        };
-
-        let mut beehive = GridBeehive::new(rows, cols);
-        for row in 0..rows {
-            beehive.set_row(row, self.layout.get(row).unwrap().clone());
+        to_grid_beehive(&self.layout).with_axes(self.axes)
+    }
+}
+
+impl From<BeehiveSerde> for Grid {
+    fn from(value: BeehiveSerde) -> Self {
+        let cols = value
+            .layout
+            .first()
+            .map(|row| row.chars().count())
+            .unwrap_or(0);
+        let mut grid = Grid::new(value.layout.len(), cols);
+        for (row, val) in value.layout.iter().enumerate() {
+            grid.set_row(row, val.clone());
         }
 
-        beehive
+        grid
     }
 }
 
 impl Into<BeehiveSwap> for BeehiveSerde {
     fn into(self) -> BeehiveSwap {
+        let scrambled = self.scrambled();
+        let locked = self.locked.clone();
         let grid: GridBeehive = self.into();
 
-        BeehiveSwap::from(grid)
+        match (scrambled, locked) {
+            (Some(scrambled), locked) => match BeehiveSwap::from_layouts(
+                grid.clone(),
+                scrambled,
+                locked.unwrap_or_else(|| layout_locked_cells(&grid)),
+            ) {
+                Some(swap) => swap,
+                None => {
+                    leptos::logging::warn!("stored scramble doesn't match its layout, reshuffling");
+                    BeehiveSwap::from(grid)
+                }
+            },
+            (None, Some(locked)) => BeehiveSwap::from(grid).with_locked_cells(locked),
+            (None, None) => BeehiveSwap::from(grid),
+        }
     }
 }
 
-pub fn append_file(beehive: GridBeehive) -> std::result::Result<(), ()> {
-    let bh = BeehiveSerde::from(beehive);
+pub fn read_file(path: &str) -> std::result::Result<Vec<BeehiveSerde>, std::io::Error> {
+    let f = std::fs::File::open(path)?;
+    let reader = std::io::BufReader::new(f);
+
+    Ok(serde_json::from_reader(reader)?)
+}
+
+pub fn append_file(beehive: impl Into<BeehiveSerde>) -> std::result::Result<(), ()> {
+    let bh: BeehiveSerde = beehive.into();
     // let json = serde_json::to_string(bh);
     let path = "./assets/beehives.json";
-    let f = std::fs::File::open(path).map_err(|_| ())?;
-    let reader = std::io::BufReader::new(f);
-    let mut beehives: Vec<BeehiveSerde> = serde_json::from_reader(reader).map_err(|_| ())?;
+    let mut beehives = read_file(path).map_err(|_| ())?;
 
     beehives.push(bh);
 
@@ -69,7 +198,8 @@ pub fn append_file(beehive: GridBeehive) -> std::result::Result<(), ()> {
     Ok(())
 }
 
-pub async fn fetch_beehive(_c: ()) -> Result<BeehiveSerde, ()> {
+// unrated beehives are picked whatever the difficulty, as long as no rated one fits
+pub async fn fetch_beehive(difficulty: Option<Difficulty>) -> Result<BeehiveSerde, ()> {
     // make the request
     let mut beehives: Vec<BeehiveSerde> = reqwasm::http::Request::get("/assets/beehives.json")
         .send()
@@ -81,6 +211,14 @@ pub async fn fetch_beehive(_c: ()) -> Result<BeehiveSerde, ()> {
         .map_err(|e| ())?;
 
     beehives.shuffle(&mut thread_rng());
+    if let Some(difficulty) = difficulty {
+        if beehives
+            .iter()
+            .any(|bh| bh.rating().map(|r| r.difficulty) == Some(difficulty))
+        {
+            beehives.retain(|bh| bh.rating().map(|r| r.difficulty) == Some(difficulty));
+        }
+    }
 
     match beehives.get(0) {
         Some(bh) => Ok(bh.clone()),
@@ -88,6 +226,19 @@ pub async fn fetch_beehive(_c: ()) -> Result<BeehiveSerde, ()> {
     }
 }
 
+// a share code in the url hash wins over a random pick from the corpus
+pub async fn load_beehive(difficulty: Option<Difficulty>) -> Result<BeehiveSerde, String> {
+    let hash = leptos::window().location().hash().unwrap_or_default();
+    let code = hash.trim_start_matches('#');
+    if !code.is_empty() {
+        return crate::share_code::decode(code).map_err(|e| e.to_string());
+    }
+
+    fetch_beehive(difficulty)
+        .await
+        .map_err(|_| "couldn't retrieve a beehive".to_string())
+}
+
 #[test]
 fn test_append() -> std::result::Result<(), ()> {
     let mut grid = GridBeehive::new(6, 6);
@@ -100,3 +251,77 @@ fn test_append() -> std::result::Result<(), ()> {
 
     append_file(grid)
 }
+
+#[test]
+fn test_stored_scramble() {
+    let serde: BeehiveSerde = serde_json::from_str(
+        r#"{
+            "layout": ["__yeah","_h__so","sofa_t","t_r_i_","a_ex__","the___"],
+            "scrambled": ["__yhae","_s__ho","sofa_t","t_r_e_","i_ax__","the___"]
+        }"#,
+    )
+    .unwrap();
+
+    let swap: BeehiveSwap = serde.clone().into();
+    assert_eq!(swap.get_shuffled_row(0), Some("__yhae".to_string()));
+    assert_eq!(swap.get_shuffled_row(4), Some("i_ax__".to_string()));
+    assert_eq!(swap.get_solved_row(0), Some("__yeah".to_string()));
+
+    // round trip keeps the very same scramble
+    let json = serde_json::to_string(&BeehiveSerde::from(swap)).unwrap();
+    let again: BeehiveSwap = serde_json::from_str::<BeehiveSerde>(&json).unwrap().into();
+    assert_eq!(again.get_shuffled_row(1), Some("_s__ho".to_string()));
+}
+
+#[test]
+fn test_invalid_scramble() {
+    let mut solved = GridBeehive::new(2, 2);
+    solved.set_row(0, "ab".to_string());
+    solved.set_row(1, "c_".to_string());
+
+    // a letter that isn't part of the solution
+    let mut scrambled = GridBeehive::new(2, 2);
+    scrambled.set_row(0, "az".to_string());
+    scrambled.set_row(1, "c_".to_string());
+    assert!(BeehiveSwap::from_layouts(solved.clone(), scrambled, vec![]).is_none());
+
+    // a block moved around
+    let mut scrambled = GridBeehive::new(2, 2);
+    scrambled.set_row(0, "a_".to_string());
+    scrambled.set_row(1, "cb".to_string());
+    assert!(BeehiveSwap::from_layouts(solved, scrambled, vec![]).is_none());
+}
+
+#[test]
+fn test_locked_cells_follow_the_layout() {
+    let stock: BeehiveSwap = BeehiveSerde::new(
+        vec![
+            "__yeah".to_string(),
+            "_h__so".to_string(),
+            "sofa_t".to_string(),
+            "t_r_i_".to_string(),
+            "a_ex__".to_string(),
+            "the___".to_string(),
+        ],
+        Some(vec![
+            "__yhae".to_string(),
+            "_s__ho".to_string(),
+            "sofa_t".to_string(),
+            "t_r_e_".to_string(),
+            "i_ax__".to_string(),
+            "the___".to_string(),
+        ]),
+        None,
+    )
+    .into();
+    assert_eq!(stock.get_locked_cells().len(), 6);
+
+    // no builtin layout is 2x2, nothing to give away
+    let small: BeehiveSwap = BeehiveSerde::new(
+        vec!["ab".to_string(), "c_".to_string()],
+        Some(vec!["ba".to_string(), "c_".to_string()]),
+        None,
+    )
+    .into();
+    assert!(small.get_locked_cells().is_empty());
+}
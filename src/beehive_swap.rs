@@ -1,32 +1,24 @@
 use core::fmt;
+use std::collections::{BTreeMap, HashMap};
 
 use rand::{seq::SliceRandom, thread_rng};
-use simple_matrix::Matrix;
 
+use crate::board::{Axes, Board, Cell, Line};
+use crate::dictionary::{Dictionary, WordSet};
 use crate::grid_beehive::GridBeehive;
+use crate::layout::LayoutRegistry;
+use crate::solver::{Budget, Outcome, SearchStats};
 
+// a solved board and a scramble of its letters, swapped back into place two at a
+// time. blocks and locked cells never move
 #[derive(Debug, Clone)]
-pub struct BeehiveSwap {
-    solved_layout: Matrix<char>,
-    shuffled_layout: Matrix<char>,
+pub struct Swap<B: Board> {
+    solved: B,
+    shuffled: B,
+    locked_cells: Vec<Cell>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
-pub enum Kind {
-    Row,
-    Col,
-    Diag,
-}
-#[derive(Clone, PartialEq, Eq, Hash, Debug, Copy)]
-pub struct Cell {
-    row: usize,
-    col: usize,
-}
-#[derive(Clone, Debug, PartialEq)]
-pub struct Line {
-    index: usize,
-    kind: Kind,
-}
+pub type BeehiveSwap = Swap<GridBeehive>;
 
 #[derive(Clone, Debug, PartialEq, Copy)]
 pub enum Color {
@@ -37,125 +29,117 @@ pub enum Color {
 
 impl From<GridBeehive> for BeehiveSwap {
     fn from(value: GridBeehive) -> Self {
-        let rows = value.rows();
-        let cols = value.cols();
-        let mut solved_layout = Matrix::new(rows, cols);
-        for r in 0..rows {
-            for c in 0..cols {
-                solved_layout.set(r, c, value.get_cell(r, c).unwrap().clone());
-            }
-        }
-
-        let shuffled_layout = solved_layout.clone();
-
+        let locked_cells = layout_locked_cells(&value);
+        Swap::new(value, locked_cells)
+    }
+}
+impl<B: Board> Swap<B> {
+    // a fresh scramble of a solved board
+    pub fn new(solved: B, locked_cells: Vec<Cell>) -> Self {
         let mut swap = Self {
-            solved_layout,
-            shuffled_layout,
+            shuffled: solved.clone(),
+            solved,
+            locked_cells,
         };
 
         swap.shuffle(20);
 
         swap
     }
-}
-impl BeehiveSwap {
+
+    // build a swap from a solution and a fixed scramble of it, rejecting scrambles
+    // that move blocks or locked cells, or that don't use the solution's letters
+    pub fn from_layouts(solved: B, scrambled: B, locked_cells: Vec<Cell>) -> Option<Self> {
+        if solved.rows() != scrambled.rows() || solved.cols() != scrambled.cols() {
+            return None;
+        }
+
+        // the scramble reads along the solution's axes
+        let mut shuffled = solved.clone();
+        for r in 0..solved.rows() {
+            for c in 0..solved.cols() {
+                shuffled.set_cell(r, c, *scrambled.get_cell(r, c).unwrap());
+            }
+        }
+
+        let swap = Self {
+            solved,
+            shuffled,
+            locked_cells,
+        };
+
+        let swappable_cells = swap.get_swappable_cells();
+        let mut solved_letters = vec![];
+        let mut shuffled_letters = vec![];
+        for cell in swap.get_cells() {
+            let solved_letter = *swap.get_solved_cell(&cell).unwrap();
+            let shuffled_letter = *swap.get_shuffled_cell(&cell).unwrap();
+            if swappable_cells.contains(&cell) {
+                solved_letters.push(solved_letter);
+                shuffled_letters.push(shuffled_letter);
+            } else if solved_letter != shuffled_letter {
+                return None;
+            }
+        }
+        solved_letters.sort();
+        shuffled_letters.sort();
+        if solved_letters != shuffled_letters {
+            return None;
+        }
+
+        Some(swap)
+    }
+
     pub fn rows(&self) -> usize {
-        self.solved_layout.rows()
+        self.solved.rows()
     }
     pub fn cols(&self) -> usize {
-        self.solved_layout.cols()
+        self.solved.cols()
     }
     pub fn diags(&self) -> usize {
-        self.solved_layout.rows() + self.solved_layout.cols() - 1
+        self.solved.diags()
+    }
+    pub fn axes(&self) -> Axes {
+        self.solved.axes()
     }
     // getters solved layout
     pub fn get_solved_line(&self, line: &Line) -> Option<String> {
-        match line.kind {
-            Kind::Row => self.get_solved_row(line.index),
-            Kind::Col => self.get_solved_col(line.index),
-            Kind::Diag => self.get_solved_diag(line.index),
-        }
+        self.solved.get_line(line)
     }
     pub fn get_solved_row(&self, row: usize) -> Option<String> {
-        self.solved_layout.get_row(row).map(|iter| {
-            let str: String = iter.collect();
-            str
-        })
+        self.solved.get_row(row)
     }
     pub fn get_solved_col(&self, col: usize) -> Option<String> {
-        self.solved_layout.get_col(col).map(|iter| {
-            let str: String = iter.collect();
-            str
-        })
+        self.solved.get_col(col)
     }
     pub fn get_solved_diag(&self, diag: usize) -> Option<String> {
-        if diag >= self.rows() + self.cols() - 1 {
-            return None;
-        }
-
-        let res: String = (0..(diag + 1))
-            .map(|i| {
-                self.get_solved_cell(&Cell {
-                    row: diag - i,
-                    col: i,
-                })
-            })
-            .filter(|opt| opt.is_some())
-            .map(|opt| opt.unwrap())
-            .collect();
-
-        Some(res)
+        self.solved.get_diag(diag)
     }
     pub fn get_solved_cell(&self, cell: &Cell) -> Option<&char> {
-        self.solved_layout.get(cell.row, cell.col)
+        self.solved.get_cell(cell.row(), cell.col())
     }
 
     // getters shuffled layout
     pub fn get_shuffled_line(&self, line: &Line) -> Option<String> {
-        match line.kind {
-            Kind::Row => self.get_shuffled_row(line.index),
-            Kind::Col => self.get_shuffled_col(line.index),
-            Kind::Diag => self.get_shuffled_diag(line.index),
-        }
+        self.shuffled.get_line(line)
     }
     pub fn get_shuffled_row(&self, row: usize) -> Option<String> {
-        self.shuffled_layout.get_row(row).map(|iter| {
-            let str: String = iter.collect();
-            str
-        })
+        self.shuffled.get_row(row)
     }
     pub fn get_shuffled_col(&self, col: usize) -> Option<String> {
-        self.shuffled_layout.get_col(col).map(|iter| {
-            let str: String = iter.collect();
-            str
-        })
+        self.shuffled.get_col(col)
     }
     pub fn get_shuffled_diag(&self, diag: usize) -> Option<String> {
-        if diag >= self.rows() + self.cols() - 1 {
-            return None;
-        }
-
-        let res: String = (0..(diag + 1))
-            .map(|i| {
-                self.get_shuffled_cell(&Cell {
-                    row: diag - i,
-                    col: i,
-                })
-            })
-            .filter(|opt| opt.is_some())
-            .map(|opt| opt.unwrap())
-            .collect();
-
-        Some(res)
+        self.shuffled.get_diag(diag)
     }
     pub fn get_shuffled_cell(&self, cell: &Cell) -> Option<&char> {
-        self.shuffled_layout.get(cell.row, cell.col)
+        self.shuffled.get_cell(cell.row(), cell.col())
     }
     pub fn get_cells(&self) -> Vec<Cell> {
         let mut cells = vec![];
         for r in 0..self.rows() {
             for c in 0..self.cols() {
-                cells.push(Cell { row: r, col: c });
+                cells.push(Cell::new(r, c));
             }
         }
 
@@ -165,261 +149,35 @@ impl BeehiveSwap {
         self.get_cells()
             .into_iter()
             .filter(|c| self.get_solved_cell(c) != Some(&'_'))
-            .filter(|c| c != &Cell { row: 0, col: 2 })
-            .filter(|c| c != &Cell { row: 2, col: 2 })
-            .filter(|c| c != &Cell { row: 2, col: 3 })
-            .filter(|c| c != &Cell { row: 2, col: 5 })
-            .filter(|c| c != &Cell { row: 3, col: 2 })
-            .filter(|c| c != &Cell { row: 5, col: 0 })
+            .filter(|c| !self.locked_cells.contains(c))
             .collect()
     }
-
-    fn _get_solved_words(&self, cell: &Cell) -> Vec<String> {
-        // hardcode ftw
-        match cell {
-            Cell { row: 0, col: 2 } => vec![
-                self.get_solved_row(0).unwrap()[2..6].to_string(),
-                self.get_solved_diag(2).unwrap(),
-            ],
-            Cell { row: 0, col: 3 } => vec![self.get_solved_row(0).unwrap()[2..6].to_string()],
-            Cell { row: 0, col: 4 } => vec![
-                self.get_solved_row(0).unwrap()[2..6].to_string(),
-                self.get_solved_col(4).unwrap()[0..2].to_string(),
-            ],
-            Cell { row: 0, col: 5 } => vec![
-                self.get_solved_row(0).unwrap()[2..6].to_string(),
-                self.get_solved_col(5).unwrap()[0..3].to_string(),
-                self.get_solved_diag(5).unwrap()[2..6].to_string(),
-            ],
-            Cell { row: 1, col: 1 } => vec![self.get_solved_diag(2).unwrap()],
-            Cell { row: 1, col: 4 } => vec![
-                self.get_solved_row(1).unwrap()[4..6].to_string(),
-                self.get_solved_diag(5).unwrap()[2..6].to_string(),
-            ],
-            Cell { row: 1, col: 5 } => vec![
-                self.get_solved_row(1).unwrap()[4..6].to_string(),
-                self.get_solved_col(5).unwrap()[0..3].to_string(),
-            ],
-            Cell { row: 2, col: 0 } => vec![
-                self.get_solved_row(2).unwrap()[0..4].to_string(),
-                self.get_solved_col(0).unwrap()[2..6].to_string(),
-                self.get_solved_diag(2).unwrap(),
-            ],
-            Cell { row: 2, col: 1 } => vec![
-                self.get_solved_row(2).unwrap()[0..4].to_string(),
-                self.get_solved_row(1).unwrap()[1..3].to_string(),
-                self.get_solved_diag(3).unwrap()[0..2].to_string(),
-            ],
-            Cell { row: 3, col: 0 } => vec![
-                self.get_solved_col(0).unwrap()[2..6].to_string(),
-                self.get_solved_diag(3).unwrap()[0..2].to_string(),
-            ],
-            Cell { row: 3, col: 4 } => vec![self.get_solved_diag(7).unwrap()],
-            Cell { row: 4, col: 0 } => vec![self.get_solved_col(0).unwrap()[2..6].to_string()],
-            Cell { row: 4, col: 2 } => vec![
-                self.get_solved_row(4).unwrap()[2..4].to_string(),
-                self.get_solved_col(2).unwrap()[2..6].to_string(),
-                self.get_solved_diag(6).unwrap()[0..2].to_string(),
-            ],
-            Cell { row: 4, col: 3 } => vec![
-                self.get_solved_row(4).unwrap()[2..4].to_string(),
-                self.get_solved_diag(7).unwrap(),
-            ],
-            Cell { row: 5, col: 1 } => vec![
-                self.get_solved_row(5).unwrap()[0..3].to_string(),
-                self.get_solved_diag(6).unwrap()[0..2].to_string(),
-            ],
-            Cell { row: 5, col: 2 } => vec![
-                self.get_solved_row(5).unwrap()[0..3].to_string(),
-                self.get_solved_col(2).unwrap()[2..6].to_string(),
-                self.get_solved_diag(7).unwrap(),
-            ],
-            _ => vec![],
-        }
+    pub fn get_locked_cells(&self) -> &Vec<Cell> {
+        &self.locked_cells
     }
 
-    fn get_cell_color(&self, cell: &Cell) -> Color {
-        if &self.get_solved_cell(cell) == &self.get_shuffled_cell(cell) {
+    // green in place, yellow when the letter belongs elsewhere in a slot going
+    // through the cell, along the active axes only
+    pub fn get_cell_color(&self, cell: &Cell) -> Color {
+        if self.get_solved_cell(cell) == self.get_shuffled_cell(cell) {
             return Color::Green;
         };
 
-        // hardcode ftw
-        // code very easy to understand and maintain
-        let is_yellow = match cell {
-            Cell { row: 0, col: 3 } => {
-                let r_word_solved = self.get_solved_row(0).unwrap()[2..6].to_string();
-                let r_word_shuffled = self.get_shuffled_row(0).unwrap()[2..6].to_string();
-
-                is_yellow(r_word_solved, r_word_shuffled, 1)
-            }
-            Cell { row: 0, col: 4 } => {
-                let r_word_solved = self.get_solved_row(0).unwrap()[2..6].to_string();
-                let r_word_shuffled = self.get_shuffled_row(0).unwrap()[2..6].to_string();
-                let r_yellow = is_yellow(r_word_solved, r_word_shuffled, 2);
-
-                let c_word_solved = self.get_solved_col(4).unwrap()[0..2].to_string();
-                let c_word_shuffled = self.get_shuffled_col(4).unwrap()[0..2].to_string();
-                let c_yellow = is_yellow(c_word_solved, c_word_shuffled, 0);
-
-                r_yellow || c_yellow
-            }
-            Cell { row: 0, col: 5 } => {
-                let r_word_solved = self.get_solved_row(0).unwrap()[2..6].to_string();
-                let r_word_shuffled = self.get_shuffled_row(0).unwrap()[2..6].to_string();
-                let r_yellow = is_yellow(r_word_solved, r_word_shuffled, 3);
-
-                let c_word_solved = self.get_solved_col(5).unwrap()[0..3].to_string();
-                let c_word_shuffled = self.get_shuffled_col(5).unwrap()[0..3].to_string();
-                let c_yellow = is_yellow(c_word_solved, c_word_shuffled, 0);
-
-                let d_word_solved = self.get_solved_diag(5).unwrap()[2..6].to_string();
-                let d_word_shuffled = self.get_shuffled_diag(5).unwrap()[2..6].to_string();
-                let d_yellow = is_yellow(d_word_solved, d_word_shuffled, 3);
-
-                r_yellow || c_yellow || d_yellow
-            }
-            Cell { row: 1, col: 1 } => {
-                let c_word_solved = self.get_solved_col(1).unwrap()[1..3].to_string();
-                let c_word_shuffled = self.get_shuffled_col(1).unwrap()[1..3].to_string();
-                let c_yellow = is_yellow(c_word_solved, c_word_shuffled, 0);
-
-                let d_word_solved = self.get_solved_diag(2).unwrap();
-                let d_word_shuffled = self.get_shuffled_diag(2).unwrap();
-                let d_yellow = is_yellow(d_word_solved, d_word_shuffled, 1);
-
-                c_yellow || d_yellow
-            }
-            Cell { row: 1, col: 4 } => {
-                let r_word_solved = self.get_solved_row(1).unwrap()[4..6].to_string();
-                let r_word_shuffled = self.get_shuffled_row(1).unwrap()[4..6].to_string();
-                let r_yellow = is_yellow(r_word_solved, r_word_shuffled, 0);
-
-                let c_word_solved = self.get_solved_col(4).unwrap()[0..2].to_string();
-                let c_word_shuffled = self.get_shuffled_col(4).unwrap()[0..2].to_string();
-                let c_yellow = is_yellow(c_word_solved, c_word_shuffled, 1);
-
-                let d_word_solved = self.get_solved_diag(5).unwrap()[2..6].to_string();
-                let d_word_shuffled = self.get_shuffled_diag(5).unwrap()[2..6].to_string();
-                let d_yellow = is_yellow(d_word_solved, d_word_shuffled, 2);
-
-                r_yellow || c_yellow || d_yellow
-            }
-            Cell { row: 1, col: 5 } => {
-                let r_word_solved = self.get_solved_row(1).unwrap()[4..6].to_string();
-                let r_word_shuffled = self.get_shuffled_row(1).unwrap()[4..6].to_string();
-                let r_yellow = is_yellow(r_word_solved, r_word_shuffled, 1);
-
-                let c_word_solved = self.get_solved_col(5).unwrap()[0..3].to_string();
-                let c_word_shuffled = self.get_shuffled_col(5).unwrap()[0..3].to_string();
-                let c_yellow = is_yellow(c_word_solved, c_word_shuffled, 1);
-
-                r_yellow || c_yellow
-            }
-            Cell { row: 2, col: 0 } => {
-                let r_word_solved = self.get_solved_row(2).unwrap()[0..4].to_string();
-                let r_word_shuffled = self.get_shuffled_row(2).unwrap()[0..4].to_string();
-                let r_yellow = is_yellow(r_word_solved, r_word_shuffled, 0);
-
-                let c_word_solved = self.get_solved_col(0).unwrap()[2..6].to_string();
-                let c_word_shuffled = self.get_shuffled_col(0).unwrap()[2..6].to_string();
-                let c_yellow = is_yellow(c_word_solved, c_word_shuffled, 0);
-
-                let d_word_solved = self.get_solved_diag(2).unwrap();
-                let d_word_shuffled = self.get_shuffled_diag(2).unwrap();
-                let d_yellow = is_yellow(d_word_solved, d_word_shuffled, 0);
-
-                r_yellow || c_yellow || d_yellow
-            }
-            Cell { row: 2, col: 1 } => {
-                let r_word_solved = self.get_solved_row(2).unwrap()[0..4].to_string();
-                let r_word_shuffled = self.get_shuffled_row(2).unwrap()[0..4].to_string();
-                let r_yellow = is_yellow(r_word_solved, r_word_shuffled, 1);
-
-                let c_word_solved = self.get_solved_col(1).unwrap()[1..3].to_string();
-                let c_word_shuffled = self.get_shuffled_col(1).unwrap()[1..3].to_string();
-                let c_yellow = is_yellow(c_word_solved, c_word_shuffled, 1);
-
-                let d_word_solved = self.get_solved_diag(3).unwrap()[0..2].to_string();
-                let d_word_shuffled = self.get_shuffled_diag(3).unwrap()[0..2].to_string();
-                let d_yellow = is_yellow(d_word_solved, d_word_shuffled, 1);
-
-                r_yellow || c_yellow || d_yellow
-            }
-            Cell { row: 3, col: 0 } => {
-                let c_word_solved = self.get_solved_col(0).unwrap()[2..6].to_string();
-                let c_word_shuffled = self.get_shuffled_col(0).unwrap()[2..6].to_string();
-                let c_yellow = is_yellow(c_word_solved, c_word_shuffled, 1);
-
-                let d_word_solved = self.get_solved_diag(3).unwrap()[0..2].to_string();
-                let d_word_shuffled = self.get_shuffled_diag(3).unwrap()[0..2].to_string();
-                let d_yellow = is_yellow(d_word_solved, d_word_shuffled, 0);
-
-                c_yellow || d_yellow
-            }
-            Cell { row: 3, col: 4 } => {
-                let d_word_solved = self.get_solved_diag(7).unwrap();
-                let d_word_shuffled = self.get_shuffled_diag(7).unwrap();
-                is_yellow(d_word_solved, d_word_shuffled, 2)
-            }
-            Cell { row: 4, col: 0 } => {
-                let c_word_solved = self.get_solved_col(0).unwrap()[2..6].to_string();
-                let c_word_shuffled = self.get_shuffled_col(0).unwrap()[2..6].to_string();
-                is_yellow(c_word_solved, c_word_shuffled, 2)
-            }
-            Cell { row: 4, col: 2 } => {
-                let r_word_solved = self.get_solved_row(4).unwrap()[2..4].to_string();
-                let r_word_shuffled = self.get_shuffled_row(4).unwrap()[2..4].to_string();
-                let r_yellow = is_yellow(r_word_solved, r_word_shuffled, 0);
-
-                let c_word_solved = self.get_solved_col(2).unwrap()[2..6].to_string();
-                let c_word_shuffled = self.get_shuffled_col(2).unwrap()[2..6].to_string();
-                let c_yellow = is_yellow(c_word_solved, c_word_shuffled, 2);
-
-                let d_word_solved = self.get_solved_diag(6).unwrap()[0..2].to_string();
-                let d_word_shuffled = self.get_shuffled_diag(6).unwrap()[0..2].to_string();
-                let d_yellow = is_yellow(d_word_solved, d_word_shuffled, 1);
-
-                r_yellow || c_yellow || d_yellow
-            }
-            Cell { row: 4, col: 3 } => {
-                let r_word_solved = self.get_solved_row(4).unwrap()[2..4].to_string();
-                let r_word_shuffled = self.get_shuffled_row(4).unwrap()[2..4].to_string();
-                let r_yellow = is_yellow(r_word_solved, r_word_shuffled, 1);
-
-                let d_word_solved = self.get_solved_diag(7).unwrap();
-                let d_word_shuffled = self.get_shuffled_diag(7).unwrap();
-                let d_yellow = is_yellow(d_word_solved, d_word_shuffled, 1);
-
-                r_yellow || d_yellow
-            }
-            Cell { row: 5, col: 1 } => {
-                let r_word_solved = self.get_solved_row(5).unwrap()[0..3].to_string();
-                let r_word_shuffled = self.get_shuffled_row(5).unwrap()[0..3].to_string();
-                let r_yellow = is_yellow(r_word_solved, r_word_shuffled, 1);
-
-                let d_word_solved = self.get_solved_diag(6).unwrap()[0..2].to_string();
-                let d_word_shuffled = self.get_shuffled_diag(6).unwrap()[0..2].to_string();
-                let d_yellow = is_yellow(d_word_solved, d_word_shuffled, 0);
-
-                r_yellow || d_yellow
-            }
-            Cell { row: 5, col: 2 } => {
-                let r_word_solved = self.get_solved_row(5).unwrap()[0..3].to_string();
-                let r_word_shuffled = self.get_shuffled_row(5).unwrap()[0..3].to_string();
-                let r_yellow = is_yellow(r_word_solved, r_word_shuffled, 2);
-
-                let c_word_solved = self.get_solved_col(2).unwrap()[2..6].to_string();
-                let c_word_shuffled = self.get_shuffled_col(2).unwrap()[2..6].to_string();
-                let c_yellow = is_yellow(c_word_solved, c_word_shuffled, 3);
-
-                let d_word_solved = self.get_solved_diag(7).unwrap();
-                let d_word_shuffled = self.get_shuffled_diag(7).unwrap();
-                let d_yellow = is_yellow(d_word_solved, d_word_shuffled, 0);
-
-                r_yellow || c_yellow || d_yellow
-            }
-            _ => false,
+        let (solved, shuffled) = (&self.solved, &self.shuffled);
+        let word = |grid: &B, cells: &[Cell]| -> String {
+            cells
+                .iter()
+                .map(|c| *grid.get_cell(c.row(), c.col()).unwrap())
+                .collect()
         };
+        let is_yellow = solved.slots().iter().any(|slot| {
+            slot.cells
+                .iter()
+                .position(|c| c.row() == cell.row() && c.col() == cell.col())
+                .is_some_and(|i| {
+                    is_yellow(word(solved, &slot.cells), word(shuffled, &slot.cells), i)
+                })
+        });
 
         if is_yellow {
             Color::Yellow
@@ -448,7 +206,7 @@ impl BeehiveSwap {
     // }
 
     pub fn set_shuffled_cell(&mut self, cell: &Cell, val: char) {
-        self.shuffled_layout.set(cell.row, cell.col, val);
+        self.shuffled.set_cell(cell.row(), cell.col(), val);
     }
 
     pub fn swap(&mut self, cell_a: &Cell, cell_b: &Cell) {
@@ -472,6 +230,165 @@ impl BeehiveSwap {
             self.swap(cell_a, cell_b);
         }
     }
+
+    pub fn is_solved(&self) -> bool {
+        self.get_cells()
+            .iter()
+            .all(|cell| self.get_solved_cell(cell) == self.get_shuffled_cell(cell))
+    }
+
+    // any arrangement wins as long as every slot spells a word and no word is
+    // used twice, the stored solution always does
+    pub fn is_won(&self, words: &WordSet) -> bool {
+        if self.is_solved() {
+            return true;
+        }
+
+        let mut slot_words = self.shuffled_grid().slot_words();
+        if !slot_words.iter().all(|word| words.contains(word)) {
+            return false;
+        }
+        let cnt = slot_words.len();
+        slot_words.sort();
+        slot_words.dedup();
+
+        slot_words.len() == cnt
+    }
+
+    pub fn solved_grid(&self) -> B {
+        self.solved.clone()
+    }
+    pub fn shuffled_grid(&self) -> B {
+        self.shuffled.clone()
+    }
+
+    // fewest swaps putting every letter back in place. a swap fixes a whole cycle
+    // of misplaced letters but one, and letters repeat so there are several ways to
+    // split them into cycles: all of them are searched for the most cycles
+    pub fn min_swaps(&self) -> usize {
+        let mut edges = LetterEdges::new();
+        let mut misplaced = 0;
+        for cell in self.get_swappable_cells() {
+            let needed = *self.get_solved_cell(&cell).unwrap();
+            let held = *self.get_shuffled_cell(&cell).unwrap();
+            if needed != held {
+                *edges.entry((needed, held)).or_default() += 1;
+                misplaced += 1;
+            }
+        }
+
+        let cycles = max_cycles(&edges, &mut HashMap::new());
+
+        misplaced - cycles
+    }
+
+    // swappable cells that start green or yellow
+    pub fn hint_count(&self) -> usize {
+        self.get_swappable_cells()
+            .iter()
+            .filter(|cell| self.get_cell_color(cell) != Color::White)
+            .count()
+    }
+
+    // valid arrangements of the letters with the locked cells in place, up to limit.
+    // anything but 1 means a player can be marked wrong with a valid grid
+    pub fn count_solutions(
+        &self,
+        dictionary: &Dictionary,
+        limit: usize,
+        budget: Budget,
+    ) -> (Outcome<usize>, SearchStats) {
+        let mut layout = self.solved.clone();
+        let swappable_cells = self.get_swappable_cells();
+        for cell in &swappable_cells {
+            layout.set_cell(cell.row(), cell.col(), '\0');
+        }
+        let letters: Vec<char> = swappable_cells
+            .iter()
+            .map(|cell| *self.get_shuffled_cell(cell).unwrap())
+            .collect();
+
+        layout.count_fills(Some(&letters), dictionary, limit, budget)
+    }
+
+    // reshuffles from the solution so the new locked cells stay in place
+    pub fn with_locked_cells(mut self, locked_cells: Vec<Cell>) -> Self {
+        self.locked_cells = locked_cells;
+        self.shuffled = self.solved.clone();
+        self.shuffle(20);
+
+        self
+    }
+}
+
+// cells given away from the start by the builtin layout the board was made from,
+// none when it comes from no builtin layout
+pub fn layout_locked_cells(grid: &GridBeehive) -> Vec<Cell> {
+    LayoutRegistry::builtin()
+        .layout_of(grid)
+        .map(|layout| layout.locked.clone())
+        .unwrap_or_default()
+}
+
+// misplaced letters as a graph: an edge from the letter a cell needs to the one it
+// holds, counted
+type LetterEdges = BTreeMap<(char, char), usize>;
+// the most cycles found for the edges left, keyed by the counted edges
+type SeenSplits = HashMap<Vec<((char, char), usize)>, usize>;
+
+// most cycles the edges split into. every split has a cycle through the first
+// letter, so each simple one through it is tried and the rest solved the same
+// way. there is a node per letter, few enough to remember every state
+fn max_cycles(edges: &LetterEdges, seen: &mut SeenSplits) -> usize {
+    let Some(&(start, _)) = edges.keys().next() else {
+        return 0;
+    };
+    let key: Vec<((char, char), usize)> = edges.iter().map(|(e, cnt)| (*e, *cnt)).collect();
+    if let Some(cycles) = seen.get(&key) {
+        return *cycles;
+    }
+
+    let mut cycles = vec![];
+    simple_cycles(edges, &mut vec![start], &mut cycles);
+    let best = cycles
+        .into_iter()
+        .map(|cycle| {
+            let mut rest = edges.clone();
+            for edge in cycle {
+                let cnt = rest.get_mut(&edge).unwrap();
+                *cnt -= 1;
+                if *cnt == 0 {
+                    rest.remove(&edge);
+                }
+            }
+            1 + max_cycles(&rest, seen)
+        })
+        .max()
+        .unwrap_or(0);
+    seen.insert(key, best);
+
+    best
+}
+
+// cycles back to the first letter of the path that don't visit a letter twice
+fn simple_cycles(edges: &LetterEdges, path: &mut Vec<char>, cycles: &mut Vec<Vec<(char, char)>>) {
+    let last = *path.last().unwrap();
+    let next: Vec<char> = edges
+        .keys()
+        .filter(|(a, _)| *a == last)
+        .map(|(_, b)| *b)
+        .collect();
+    for b in next {
+        if b == path[0] {
+            let mut cycle: Vec<(char, char)> = path.windows(2).map(|w| (w[0], w[1])).collect();
+            cycle.push((last, b));
+            cycles.push(cycle);
+        } else if !path.contains(&b) {
+            path.push(b);
+            simple_cycles(edges, path, cycles);
+            path.pop();
+        }
+    }
 }
 
 fn is_yellow(solved: String, shuffled: String, index: usize) -> bool {
@@ -504,7 +421,7 @@ impl fmt::Display for BeehiveSwap {
                 write!(
                     f,
                     "{} ",
-                    self.get_solved_cell(&Cell { row: i, col: j })
+                    self.get_solved_cell(&Cell::new(i, j))
                         .unwrap_or(&'\0')
                         .to_string()
                         .replace('\0', "⬡")
@@ -524,7 +441,7 @@ impl fmt::Display for BeehiveSwap {
                 write!(
                     f,
                     "{} ",
-                    self.get_shuffled_cell(&Cell { row: i, col: j })
+                    self.get_shuffled_cell(&Cell::new(i, j))
                         .unwrap_or(&'\0')
                         .to_string()
                         .replace('\0', "⬡")
@@ -545,9 +462,22 @@ pub mod ui {
     use super::*;
     #[component]
     pub fn BeehiveSwapComponent(initial_beehive: BeehiveSwap) -> impl IntoView {
+        let share_code = crate::share_code::encode(&crate::beehive_serde::BeehiveSerde::from(
+            initial_beehive.clone(),
+        ));
         let (beehive, set_beehive) = create_signal(initial_beehive);
         let (candidate, swap) = create_signal::<Option<Cell>>(None);
         let (cnt, cnt_set) = create_signal(0);
+        let words = create_local_resource(|| (), crate::dictionary::fetch_word_set);
+        // until the words are there only the stored solution wins
+        let won = move || {
+            beehive.with(|bh| {
+                words.with(|words| match words {
+                    Some(Ok(words)) => bh.is_won(words),
+                    _ => bh.is_solved(),
+                })
+            })
+        };
 
         let rows = beehive.with(|bh| bh.rows());
         let cols = beehive.with(|bh| bh.cols());
@@ -562,7 +492,7 @@ pub mod ui {
                         let offsets_before = (0..r).map(|_| view! { <div class="offset" /> }).collect_view();
                         let cells = (0..cols)
                             .map(|c| {
-                                let cell = Cell{row: r, col: c};
+                                let cell = Cell::new(r, c);
                                 let letter = move || beehive.with(|bh| bh.get_shuffled_cell(&cell).unwrap().clone());
                                 let color = move || beehive.with(|bh| bh.get_cell_color(&cell));
 
@@ -572,7 +502,7 @@ pub mod ui {
                                     _l  => view! {
                                         <div
                                             on:click=move |_| {
-                                                if color() == Color::Green {
+                                                if color() == Color::Green || won() {
                                                     return;
                                                 }
                                                 if candidate.get().is_none() {
@@ -590,7 +520,7 @@ pub mod ui {
                                                 }
                                             }
                                             class="cell"
-                                            class:is-green = move || color() == Color::Green
+                                            class:is-green = move || color() == Color::Green || won()
                                             class:is-yellow = move || color() == Color::Yellow
                                             class:is-swap = move || candidate.get() == Some(cell)
                                         >
@@ -608,6 +538,13 @@ pub mod ui {
             <div>
                 "Swap counter: " {cnt}
             </div>
+            {move || won().then(|| view! { <div>"Solved in " {cnt} " swaps!"</div> })}
+            // boards a code can't hold go without a link
+            {share_code.ok().map(|share_code| view! {
+                <div>
+                    <a href=format!("#{}", share_code)>"Link to this beehive"</a>
+                </div>
+            })}
         }
     }
 }
@@ -636,3 +573,51 @@ pub mod ui {
 //         );
 //     }
 // }
+
+#[cfg(test)]
+mod test {
+    use super::BeehiveSwap;
+    use crate::board::Board;
+    use crate::dictionary::WordSet;
+    use crate::grid_beehive::GridBeehive;
+
+    fn grid(rows: [&str; 2]) -> GridBeehive {
+        let mut grid = GridBeehive::new(2, 2);
+        for (r, row) in rows.iter().enumerate() {
+            grid.set_row(r, row.to_string());
+        }
+
+        grid
+    }
+
+    #[test]
+    fn wins_with_another_arrangement() {
+        let solved = grid(["ab", "cd"]);
+        // the transpose spells the same words across and down
+        let swap = BeehiveSwap::from_layouts(solved.clone(), grid(["ac", "bd"]), vec![]).unwrap();
+        assert!(!swap.is_solved());
+
+        let words = WordSet::from_words(["ab", "cd", "ac", "bd", "cb", "bc"]);
+        assert!(swap.is_won(&words));
+
+        let words = WordSet::from_words(["ab", "cd", "ac", "bd", "cb"]);
+        assert!(!swap.is_won(&words));
+
+        let swap = BeehiveSwap::from_layouts(solved.clone(), solved, vec![]).unwrap();
+        assert!(swap.is_won(&WordSet::default()));
+    }
+    #[test]
+    fn min_swaps_with_repeated_letters() {
+        let row = |letters: &str| {
+            let mut grid = GridBeehive::new(1, letters.len());
+            grid.set_row(0, letters.to_string());
+            grid
+        };
+        // needed -> held: ab ad af bc be ca cf dc ea fa fb. shortest first takes
+        // a-f-a then a-b-c-a and is left with a-d-c-f-b-e-a, 3 cycles and 8 swaps.
+        // a-f-a a-b-e-a a-d-c-a b-c-f-b is 4 cycles, so 7 swaps
+        let swap =
+            BeehiveSwap::from_layouts(row("aaabbccdeff"), row("bdfceafcaab"), vec![]).unwrap();
+        assert_eq!(swap.min_swaps(), 7);
+    }
+}
@@ -0,0 +1,859 @@
+use rand::{rngs::StdRng, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
+use simple_matrix::Matrix;
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt,
+    time::Duration,
+};
+
+use crate::dictionary::Dictionary;
+use crate::hex::Hex;
+use crate::solver::{now_ms, Budget, Outcome, Problem, Search, SearchOptions, SearchStats, Solver};
+
+// nodes a layout gets before a black is added to it
+const LAYOUT_NODES: usize = 50_000;
+// letters left on both sides of an added black
+const MIN_PART: usize = 2;
+
+// line directions, the square grid only reads rows and cols
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Kind {
+    Row,
+    Col,
+    Diag,
+}
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct Cell {
+    row: usize,
+    col: usize,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub struct Line {
+    index: usize,
+    kind: Kind,
+}
+impl Cell {
+    pub fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+    pub fn row(&self) -> usize {
+        self.row
+    }
+    pub fn col(&self) -> usize {
+        self.col
+    }
+    pub fn hex(&self) -> Hex {
+        Hex::from_cell(self.row, self.col)
+    }
+}
+impl Kind {
+    // the way a line reads on the hex lattice, diags go from bottom left to top right
+    pub fn direction(&self) -> Hex {
+        match self {
+            Kind::Row => Hex::new(1, 0),
+            Kind::Col => Hex::new(0, 1),
+            Kind::Diag => Hex::new(1, -1),
+        }
+    }
+    // the index of the line of this kind going through a hex
+    pub fn index_of(&self, hex: Hex) -> i32 {
+        match self {
+            Kind::Row => hex.r,
+            Kind::Col => hex.q,
+            Kind::Diag => hex.q + hex.r,
+        }
+    }
+}
+impl Line {
+    pub fn new(kind: Kind, index: usize) -> Self {
+        Self { index, kind }
+    }
+    pub fn kind(&self) -> &Kind {
+        &self.kind
+    }
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            Kind::Row => "row",
+            Kind::Col => "col",
+            Kind::Diag => "diag",
+        };
+        write!(f, "{} {}", kind, self.index)
+    }
+}
+
+// a run of 2 or more open cells along a line, where a word goes
+#[derive(Clone, Debug, PartialEq)]
+pub struct Slot {
+    pub line: Line,
+    pub cells: Vec<Cell>,
+}
+
+// the directions words are read along, any subset of the three hex axes.
+// drawn, rows are flat, cols go down to the right and diags up to the right
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Axes {
+    row: bool,
+    col: bool,
+    diag: bool,
+}
+impl Axes {
+    pub const ALL: Axes = Axes {
+        row: true,
+        col: true,
+        diag: true,
+    };
+    pub const SQUARE: Axes = Axes {
+        row: true,
+        col: true,
+        diag: false,
+    };
+
+    pub fn new(kinds: &[Kind]) -> Self {
+        Self {
+            row: kinds.contains(&Kind::Row),
+            col: kinds.contains(&Kind::Col),
+            diag: kinds.contains(&Kind::Diag),
+        }
+    }
+    pub fn contains(&self, kind: &Kind) -> bool {
+        match kind {
+            Kind::Row => self.row,
+            Kind::Col => self.col,
+            Kind::Diag => self.diag,
+        }
+    }
+    pub fn kinds(&self) -> Vec<Kind> {
+        [Kind::Row, Kind::Col, Kind::Diag]
+            .into_iter()
+            .filter(|kind| self.contains(kind))
+            .collect()
+    }
+    pub fn is_all(&self) -> bool {
+        *self == Axes::ALL
+    }
+}
+impl Default for Axes {
+    fn default() -> Self {
+        Axes::ALL
+    }
+}
+impl fmt::Display for Axes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<String> = self
+            .kinds()
+            .iter()
+            .map(|kind| match kind {
+                Kind::Row => "row".to_string(),
+                Kind::Col => "col".to_string(),
+                Kind::Diag => "diag".to_string(),
+            })
+            .collect();
+        write!(f, "{}", names.join(" "))
+    }
+}
+impl std::str::FromStr for Axes {
+    type Err = String;
+
+    // names split by spaces or commas, like "row diag" or "row,col"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut kinds = vec![];
+        for name in s.split([' ', ',']).filter(|n| !n.is_empty()) {
+            kinds.push(match name {
+                "row" => Kind::Row,
+                "col" => Kind::Col,
+                "diag" => Kind::Diag,
+                _ => return Err(format!("unknown line {}, expected row, col or diag", name)),
+            });
+        }
+        if kinds.is_empty() {
+            return Err("at least one of row, col or diag is needed".to_string());
+        }
+
+        Ok(Axes::new(&kinds))
+    }
+}
+impl From<Axes> for String {
+    fn from(axes: Axes) -> String {
+        axes.to_string()
+    }
+}
+impl TryFrom<String> for Axes {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+// a matrix of cells, '_' for blocks and '\0' while open, read along the lines of
+// its axes. the square grid and the hex beehive only differ by their axes, so the
+// topology, the validation and the fill all live here
+pub trait Board: Clone {
+    fn matrix(&self) -> &Matrix<char>;
+    fn matrix_mut(&mut self) -> &mut Matrix<char>;
+    fn resolved_lines(&self) -> &Vec<Line>;
+    fn resolved_lines_mut(&mut self) -> &mut Vec<Line>;
+    fn axes(&self) -> Axes;
+
+    fn rows(&self) -> usize {
+        self.matrix().rows()
+    }
+    fn cols(&self) -> usize {
+        self.matrix().cols()
+    }
+    fn diags(&self) -> usize {
+        self.rows() + self.cols() - 1
+    }
+
+    // getters
+    fn get_cell(&self, row: usize, col: usize) -> Option<&char> {
+        self.matrix().get(row, col)
+    }
+    fn get_hex(&self, hex: Hex) -> Option<&char> {
+        hex.to_cell().and_then(|(row, col)| self.get_cell(row, col))
+    }
+    fn get_line(&self, line: &Line) -> Option<String> {
+        let cnt = match line.kind {
+            Kind::Row => self.rows(),
+            Kind::Col => self.cols(),
+            Kind::Diag => self.diags(),
+        };
+        if line.index >= cnt {
+            return None;
+        }
+
+        let res: String = self
+            .line_cells(line)
+            .iter()
+            .map(|cell| *self.get_cell(cell.row, cell.col).unwrap())
+            .collect();
+
+        Some(res)
+    }
+    fn get_row(&self, row: usize) -> Option<String> {
+        self.get_line(&Line::new(Kind::Row, row))
+    }
+    fn get_col(&self, col: usize) -> Option<String> {
+        self.get_line(&Line::new(Kind::Col, col))
+    }
+    fn get_diag(&self, diag: usize) -> Option<String> {
+        self.get_line(&Line::new(Kind::Diag, diag))
+    }
+    // cells of a line in reading order, diags read from bottom left to top right
+    fn line_cells(&self, line: &Line) -> Vec<Cell> {
+        let index = line.index as i32;
+        let start = match line.kind {
+            Kind::Row => Hex::new(0, index),
+            Kind::Col => Hex::new(index, 0),
+            Kind::Diag => {
+                let r = index.min(self.rows() as i32 - 1);
+                Hex::new(index - r, r)
+            }
+        };
+
+        let mut cells = vec![];
+        let mut hex = start;
+        while self.get_hex(hex).is_some() {
+            let (row, col) = hex.to_cell().unwrap();
+            cells.push(Cell { row, col });
+            hex = hex + line.kind.direction();
+        }
+
+        cells
+    }
+    // the lines of the active axes, rows then cols then diags
+    fn lines(&self) -> Vec<Line> {
+        self.axes()
+            .kinds()
+            .into_iter()
+            .flat_map(|kind| {
+                let cnt = match kind {
+                    Kind::Row => self.rows(),
+                    Kind::Col => self.cols(),
+                    Kind::Diag => self.diags(),
+                };
+                (0..cnt).map(move |index| Line { index, kind })
+            })
+            .collect()
+    }
+    // the open cells linked to a cell along the active axes
+    fn neighbours(&self, cell: &Cell) -> Vec<Cell> {
+        let hex = cell.hex();
+        self.axes()
+            .kinds()
+            .into_iter()
+            .flat_map(|kind| [hex + kind.direction(), hex - kind.direction()])
+            .filter(|n| self.get_hex(*n).is_some_and(|c| c != &'_'))
+            .map(|n| {
+                let (row, col) = n.to_cell().unwrap();
+                Cell { row, col }
+            })
+            .collect()
+    }
+    // the first and last lines of their kind with an open cell, on a champfered
+    // board the outer diags are two of its six sides
+    fn is_edge(&self, line: &Line) -> bool {
+        let cnt = match line.kind {
+            Kind::Row => self.rows(),
+            Kind::Col => self.cols(),
+            Kind::Diag => self.diags(),
+        };
+        let open = |index: &usize| {
+            self.line_cells(&Line::new(line.kind, *index))
+                .iter()
+                .any(|cell| self.get_cell(cell.row, cell.col) != Some(&'_'))
+        };
+
+        (0..cnt).find(open) == Some(line.index) || (0..cnt).rev().find(open) == Some(line.index)
+    }
+    fn slots(&self) -> Vec<Slot> {
+        let mut slots = vec![];
+        for line in self.lines() {
+            let mut run: Vec<Cell> = vec![];
+            for cell in self.line_cells(&line) {
+                if self.get_cell(cell.row, cell.col) == Some(&'_') {
+                    if run.len() > 1 {
+                        slots.push(Slot {
+                            line: line.clone(),
+                            cells: run.clone(),
+                        });
+                    }
+                    run.clear();
+                } else {
+                    run.push(cell);
+                }
+            }
+            if run.len() > 1 {
+                slots.push(Slot { line, cells: run });
+            }
+        }
+
+        slots
+    }
+    // the letters of each slot, in the order of slots()
+    fn slot_words(&self) -> Vec<String> {
+        self.slots()
+            .iter()
+            .map(|slot| {
+                slot.cells
+                    .iter()
+                    .map(|c| *self.get_cell(c.row, c.col).unwrap())
+                    .collect()
+            })
+            .collect()
+    }
+
+    // setters
+    fn set_cell(&mut self, row: usize, col: usize, val: char) {
+        self.matrix_mut().set(row, col, val);
+    }
+    fn set_line(&mut self, line: &Line, val: String) {
+        for (cell, char) in self.line_cells(line).into_iter().zip(val.chars()) {
+            self.set_cell(cell.row, cell.col, char);
+        }
+    }
+    fn set_row(&mut self, row: usize, val: String) {
+        self.set_line(&Line::new(Kind::Row, row), val);
+    }
+    fn set_col(&mut self, col: usize, val: String) {
+        self.set_line(&Line::new(Kind::Col, col), val);
+    }
+    fn set_diag(&mut self, diag: usize, val: String) {
+        self.set_line(&Line::new(Kind::Diag, diag), val);
+    }
+    fn flag_resolved(&mut self, line: &Line) {
+        self.resolved_lines_mut().push(line.clone());
+    }
+
+    // incr
+    fn pattern(&self, line: &Line) -> Option<String> {
+        self.get_line(line).map(|s| s.replace('\0', "."))
+    }
+    fn next_line(&self) -> Option<Line> {
+        let mut unresolved_lines: Vec<Line> = self
+            .lines()
+            .into_iter()
+            .filter(|l| !self.resolved_lines().contains(l))
+            .collect();
+
+        unresolved_lines.sort_by(|line_a, line_b| {
+            let val_a = self.pattern(line_a).unwrap();
+            let val_b = self.pattern(line_b).unwrap();
+
+            // count the number of constrained cells, cells with already a letter or a '_' and not a '.'
+            let liberties_a = val_a.chars().filter(|c| c == &'.').count();
+            let liberties_b = val_b.chars().filter(|c| c == &'.').count();
+
+            let blacks_a = val_a.chars().filter(|c| c == &'_').count();
+            let blacks_b = val_b.chars().filter(|c| c == &'_').count();
+
+            let constrains_a = val_a.len() - liberties_a - blacks_a;
+            let constrains_b = val_b.len() - liberties_b - blacks_b;
+
+            // most constrained first
+            match constrains_b.cmp(&constrains_a) {
+                std::cmp::Ordering::Equal => liberties_b.cmp(&liberties_a),
+                res => res,
+            }
+        });
+
+        unresolved_lines.into_iter().next()
+    }
+
+    // invalid
+    fn is_invalid(&self, dictionary: &Dictionary) -> bool {
+        self.has_duplicates() || self.has_forbidden_tupples(dictionary) || self.has_isles()
+    }
+    fn has_duplicates(&self) -> bool {
+        let mut words_set: HashSet<String> = HashSet::new();
+
+        for line in self.lines() {
+            let words = self.get_line(&line).unwrap_or(String::from("\0"));
+            let words: Vec<String> = words
+                .split('_')
+                .filter(|w| w.len() > 1)
+                .filter(|w| !w.contains('\0'))
+                .map(String::from)
+                .collect();
+
+            for word in words {
+                if !words_set.insert(word) {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+    fn has_forbidden_tupples(&self, dictionary: &Dictionary) -> bool {
+        self.lines().iter().any(|line| {
+            let val = self.get_line(line).unwrap_or("".to_string());
+            dictionary.has_forbidden_tuples(val).unwrap_or(false)
+        })
+    }
+    // open cells are connected through the active axes only
+    fn has_isles(&self) -> bool {
+        let open: Vec<Cell> = (0..self.rows())
+            .flat_map(|r| (0..self.cols()).map(move |c| Cell { row: r, col: c }))
+            .filter(|cell| self.get_cell(cell.row, cell.col) != Some(&'_'))
+            .collect();
+        // any open cell will do as an anchor, no open cell means no isle
+        let Some(anchor) = open.first() else {
+            return false;
+        };
+
+        let mut visited_cells: HashSet<Cell> = HashSet::from([*anchor]);
+        let mut visit_queue = VecDeque::from([*anchor]);
+        while let Some(cell) = visit_queue.pop_back() {
+            for neighbour in self.neighbours(&cell) {
+                if visited_cells.insert(neighbour) {
+                    visit_queue.push_front(neighbour);
+                }
+            }
+        }
+
+        visited_cells.len() < open.len()
+    }
+
+    // the slots as a fill problem, cells indexed row by row
+    fn problem(&self) -> Problem {
+        let cols = self.cols();
+        Problem {
+            cells: (0..self.rows())
+                .flat_map(|r| (0..cols).map(move |c| (r, c)))
+                .map(|(r, c)| *self.get_cell(r, c).unwrap())
+                .collect(),
+            slots: self
+                .slots()
+                .iter()
+                .map(|slot| slot.cells.iter().map(|c| c.row * cols + c.col).collect())
+                .collect(),
+        }
+    }
+    fn with_solution(&self, solution: Problem) -> Self {
+        let cols = self.cols();
+        let mut board = self.clone();
+        for (i, c) in solution.cells.into_iter().enumerate() {
+            board.set_cell(i / cols, i % cols, c);
+        }
+        for line in self.lines() {
+            board.flag_resolved(&line);
+        }
+
+        board
+    }
+
+    // every layout goes through the slot solver, which applies the dictionary's
+    // forbidden parts and never repeats a word. layouts that may add blacks get
+    // one more whenever the solver can't fill them, see BlackSearch
+    fn search<'a>(
+        &self,
+        dictionary: &'a Dictionary,
+        allow_adding_blacks: bool,
+        budget: Budget,
+        options: &SearchOptions,
+    ) -> BoardSearch<'a, Self> {
+        if allow_adding_blacks {
+            BoardSearch::Blacks(Box::new(BlackSearch::new(
+                self.clone(),
+                dictionary,
+                budget,
+                options,
+            )))
+        } else {
+            BoardSearch::Slots(
+                Box::new(self.slot_solver(dictionary, budget, options)),
+                self.clone(),
+            )
+        }
+    }
+    // the solver over the slots, labelled and with the edges marked for sampling
+    fn slot_solver(
+        &self,
+        dictionary: &Dictionary,
+        budget: Budget,
+        options: &SearchOptions,
+    ) -> Solver {
+        let slots = self.slots();
+        let labels = slots
+            .iter()
+            .map(|slot| {
+                format!(
+                    "{} from {},{}",
+                    slot.line, slot.cells[0].row, slot.cells[0].col
+                )
+            })
+            .collect();
+        let edges = slots.iter().map(|slot| self.is_edge(&slot.line)).collect();
+        Solver::new(self.problem(), dictionary)
+            .with_labels(labels)
+            .with_edges(edges)
+            .with_options(options.clone())
+            .with_budget(budget)
+    }
+    // the layout with a black splitting one of its longest slots, MIN_PART letters
+    // or more on both sides. every open cell has to stay in a slot, with no isle
+    fn split_longest_slot(&self, rng: &mut StdRng) -> Option<Self> {
+        let mut slots = self.slots();
+        slots.shuffle(rng);
+        // stable, slots of the same length stay shuffled
+        slots.sort_by_key(|slot| std::cmp::Reverse(slot.cells.len()));
+
+        let open = (0..self.rows())
+            .flat_map(|r| (0..self.cols()).map(move |c| (r, c)))
+            .filter(|(r, c)| self.get_cell(*r, *c) != Some(&'_'))
+            .count();
+        for slot in slots {
+            let len = slot.cells.len();
+            let mut cuts = slot
+                .cells
+                .get(MIN_PART..len.saturating_sub(MIN_PART))
+                .unwrap_or_default()
+                .to_vec();
+            cuts.shuffle(rng);
+            for cut in cuts {
+                let mut split = self.clone();
+                split.set_cell(cut.row, cut.col, '_');
+                let covered: HashSet<Cell> =
+                    split.slots().into_iter().flat_map(|s| s.cells).collect();
+                if covered.len() == open - 1 && !split.has_isles() {
+                    return Some(split);
+                }
+            }
+        }
+
+        None
+    }
+    fn generate_within(
+        &self,
+        dictionary: &Dictionary,
+        allow_adding_blacks: bool,
+        budget: Budget,
+        options: &SearchOptions,
+    ) -> (Outcome<Self>, SearchStats) {
+        let mut search = self.search(dictionary, allow_adding_blacks, budget, options);
+        let outcome = search.run();
+
+        (outcome, search.stats())
+    }
+    fn generate(&self, dictionary: &Dictionary, allow_adding_blacks: bool) -> Option<Self> {
+        self.generate_within(
+            dictionary,
+            allow_adding_blacks,
+            Budget::default(),
+            &SearchOptions::default(),
+        )
+        .0
+        .solved()
+    }
+    // counts the fills of the open cells, up to limit, optionally using up exactly
+    // the given letters
+    fn count_fills(
+        &self,
+        letters: Option<&[char]>,
+        dictionary: &Dictionary,
+        limit: usize,
+        budget: Budget,
+    ) -> (Outcome<usize>, SearchStats) {
+        Solver::new(self.problem(), dictionary)
+            .with_budget(budget)
+            .count(letters, limit)
+    }
+
+    // one string per row, the way boards are stored
+    fn to_rows(&self) -> Vec<String> {
+        (0..self.rows()).map(|r| self.get_row(r).unwrap()).collect()
+    }
+}
+
+pub enum BoardSearch<'a, B: Board> {
+    // the solver and the layout its solution goes into
+    Slots(Box<Solver>, B),
+    Blacks(Box<BlackSearch<'a, B>>),
+}
+
+impl<'a, B: Board> Search for BoardSearch<'a, B> {
+    type Solution = B;
+
+    fn step(&mut self, max_nodes: usize) -> Option<Outcome<B>> {
+        match self {
+            BoardSearch::Blacks(search) => search.step(max_nodes),
+            BoardSearch::Slots(solver, layout) => solver
+                .step(max_nodes)
+                .map(|outcome| outcome.map(|solution| layout.with_solution(solution))),
+        }
+    }
+    fn stats(&self) -> SearchStats {
+        match self {
+            BoardSearch::Blacks(search) => search.stats(),
+            BoardSearch::Slots(solver, _) => solver.stats(),
+        }
+    }
+}
+
+// fills through the slot solver. a layout it proves unfillable, or that runs past
+// LAYOUT_NODES, gets a black splitting one of its longest slots and the solver
+// starts over on the new slots. once no slot can be split, it starts over from
+// the empty layout with another seed
+pub struct BlackSearch<'a, B: Board> {
+    dictionary: &'a Dictionary,
+    empty: B,
+    layout: B,
+    solver: Solver,
+    budget: Budget,
+    options: SearchOptions,
+    rng: StdRng,
+    // layouts given up on, and what their solvers spent. the time is taken from
+    // the clock, building solvers and placing blacks count too
+    tries: u64,
+    spent: SearchStats,
+    started: Option<f64>,
+    done: Option<Outcome<B>>,
+}
+
+impl<'a, B: Board> BlackSearch<'a, B> {
+    pub fn new(
+        empty: B,
+        dictionary: &'a Dictionary,
+        budget: Budget,
+        options: &SearchOptions,
+    ) -> Self {
+        let solver = empty.slot_solver(dictionary, layout_budget(budget.clone()), options);
+        Self {
+            dictionary,
+            layout: empty.clone(),
+            empty,
+            solver,
+            budget,
+            options: options.clone(),
+            rng: options.rng(),
+            tries: 0,
+            spent: SearchStats::default(),
+            started: None,
+            done: None,
+        }
+    }
+
+    fn finish(&mut self, outcome: Outcome<B>) -> Option<Outcome<B>> {
+        self.done = Some(outcome.clone());
+        Some(outcome)
+    }
+}
+
+// a layout's share of what's left
+fn layout_budget(budget: Budget) -> Budget {
+    Budget {
+        nodes: Some(budget.nodes.map_or(LAYOUT_NODES, |n| n.min(LAYOUT_NODES))),
+        ..budget
+    }
+}
+
+impl<'a, B: Board> Search for BlackSearch<'a, B> {
+    type Solution = B;
+
+    fn step(&mut self, max_nodes: usize) -> Option<Outcome<B>> {
+        if let Some(done) = &self.done {
+            return Some(done.clone());
+        }
+
+        let started = *self.started.get_or_insert_with(now_ms);
+        let outcome = self.solver.step(max_nodes);
+        self.spent.elapsed = Duration::from_secs_f64((now_ms() - started).max(0.0) / 1000.0);
+        let (exhausted, budget) = match outcome? {
+            Outcome::Solved(solution) => {
+                let board = self.layout.with_solution(solution);
+                return self.finish(Outcome::Solved(board));
+            }
+            Outcome::Cancelled => return self.finish(Outcome::Cancelled),
+            outcome => match self.budget.remaining(&self.stats()) {
+                Some(budget) => (matches!(outcome, Outcome::Exhausted), budget),
+                None => return self.finish(Outcome::Timeout),
+            },
+        };
+
+        self.layout = match self.layout.split_longest_slot(&mut self.rng) {
+            Some(layout) => layout,
+            // no black helps a layout that can't be filled as it is
+            None if self.tries == 0 && exhausted => return self.finish(Outcome::Exhausted),
+            None => self.empty.clone(),
+        };
+        self.spent = self.stats();
+        self.tries += 1;
+        let options = SearchOptions {
+            seed: self.options.seed.map(|seed| {
+                seed.wrapping_add(self.tries)
+                    .wrapping_mul(0x9e37_79b9_7f4a_7c15)
+            }),
+            ..self.options.clone()
+        };
+        self.solver = self
+            .layout
+            .slot_solver(self.dictionary, layout_budget(budget), &options);
+
+        None
+    }
+    fn stats(&self) -> SearchStats {
+        let mut stats = self.spent.clone();
+        stats.add(&self.solver.stats());
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Axes, Board, BoardSearch, Kind, Line};
+    use crate::dictionary::Dictionary;
+    use crate::grid::Grid;
+    use crate::grid_beehive::GridBeehive;
+    use crate::solver::{Budget, Outcome, Search, SearchOptions};
+
+    fn dictionary() -> Dictionary {
+        let words = std::fs::read_to_string("dictionaries/english/most_frequent_words").unwrap();
+        Dictionary::from_words(words.lines().map(|w| w.to_string()).collect())
+    }
+
+    // the checks every board goes through, whatever its shape
+    fn check_board<B: Board>(mut board: B) {
+        let dictionary = dictionary();
+        assert!(!board.has_isles());
+        let full = board.generate(&dictionary, false).unwrap();
+        assert!(!full.has_duplicates());
+        for word in full.slot_words() {
+            assert!(dictionary.common_words.contains(&word));
+        }
+        let (count, _) = full.count_fills(None, &dictionary, 1, Default::default());
+        assert!(matches!(count, Outcome::Solved(1)));
+
+        // a line of blocks splits every board in two
+        let middle = board.rows() / 2;
+        let blocks = "_".repeat(board.cols());
+        board.set_row(middle, blocks);
+        assert!(board.has_isles());
+    }
+
+    #[test]
+    fn boards_share_the_engine() {
+        let mut grid = Grid::new(3, 3);
+        grid.set_cell(1, 1, '_');
+        check_board(grid);
+        check_board(GridBeehive::new_5x5_honeycomb());
+    }
+
+    #[test]
+    fn lines_follow_the_axes() {
+        let grid = Grid::new(3, 5);
+        assert_eq!(grid.axes(), Axes::SQUARE);
+        assert_eq!(grid.lines().len(), 8);
+
+        let mut beehive = GridBeehive::new(3, 5);
+        assert_eq!(beehive.lines().len(), 15);
+        for d in 0..beehive.diags() {
+            let line = Line::new(Kind::Diag, d);
+            let word: String = "abc"
+                .chars()
+                .take(beehive.line_cells(&line).len())
+                .collect();
+            beehive.set_line(&line, word.clone());
+            assert_eq!(beehive.get_diag(d), Some(word));
+        }
+        assert_eq!(beehive.get_row(0), Some("abccc".to_string()));
+        assert_eq!(beehive.get_diag(7), None);
+    }
+
+    #[test]
+    fn blacks_go_through_the_slot_solver() {
+        let dictionary = dictionary();
+        let empty = GridBeehive::new_spotted_champfered(5, 8);
+        let options = SearchOptions {
+            seed: Some(1),
+            ..Default::default()
+        };
+        let (outcome, _) = empty.generate_within(&dictionary, false, Budget::default(), &options);
+        assert!(matches!(outcome, Outcome::Exhausted));
+
+        let mut search = empty.search(&dictionary, true, Budget::default(), &options);
+        assert!(matches!(search, BoardSearch::Blacks(_)));
+        let full = search.run().solved().unwrap();
+        let cells = |board: &GridBeehive, val: char| {
+            (0..board.rows())
+                .flat_map(|r| board.get_row(r).unwrap().chars().collect::<Vec<char>>())
+                .filter(|c| *c == val)
+                .count()
+        };
+        assert!(cells(&full, '_') > cells(&empty, '_'));
+        assert_eq!(cells(&full, '\0'), 0);
+        assert!(!full.has_isles());
+        assert!(!full.has_duplicates());
+        for word in full.slot_words() {
+            assert!(dictionary.common_words.contains(&word));
+        }
+    }
+
+    #[test]
+    fn edges_of_a_hexagon() {
+        let hexagon = GridBeehive::new_champfered(7, 7);
+        let edges: Vec<String> = hexagon
+            .lines()
+            .into_iter()
+            .filter(|line| hexagon.is_edge(line))
+            .map(|line| line.to_string())
+            .collect();
+        assert_eq!(
+            edges,
+            ["row 0", "row 6", "col 0", "col 6", "diag 3", "diag 9"]
+        );
+
+        // without the champfer the corners are the outermost diags
+        let rhombus = GridBeehive::new(7, 7);
+        assert!(rhombus.is_edge(&Line::new(Kind::Diag, 0)));
+        assert!(!rhombus.is_edge(&Line::new(Kind::Diag, 3)));
+    }
+}
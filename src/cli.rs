@@ -0,0 +1,571 @@
+use std::io::BufRead;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::beehive_serde::{append_file, read_file, BeehiveSerde};
+use crate::beehive_swap::BeehiveSwap;
+use crate::board::Board;
+use crate::crossword::{parse_clue_database, parse_crossword, ClueDatabase, Crossword};
+use crate::dictionary::{Dictionary, WordRanks};
+use crate::difficulty::rate;
+use crate::grid::Grid;
+use crate::grid_beehive::GridBeehive;
+use crate::ipuz::{beehive_to_ipuz, from_ipuz, IpuzGrid};
+use crate::layout::LayoutRegistry;
+use crate::layout_generator::{generate_shape, ShapeOptions, Symmetry};
+use crate::parallel::{generate_parallel, ParallelOptions};
+use crate::pdf::{booklet, BookletOptions};
+use crate::render::{HexDrawing, RenderOptions};
+use crate::share_code;
+use crate::solver::{Budget, CancelToken, Outcome, Sampling, SearchOptions};
+use crate::spelling_bee::SpellingBee;
+use crate::word_search::{generate_word_search, parse_themes, parse_words};
+
+const USAGE: &str = "usage:
+  beehive generate <rows> <cols> [--timeout <seconds>] [--nodes <n>] [--seed <n>] [--sampling <uniform|weighted|edges>] [--unique] [--trace] [--append]
+  beehive generate <rows> <cols> --threads <n> [--count <n>] [--restart <nodes>] [--timeout <seconds>] [--sampling <policy>] [--unique] [--trace] [--append]
+  beehive render <svg|png> <output> [--index <n> | --code <share-code>] [--swap] [--plain] [--size <px>]
+  beehive generate --layout <name> [--layouts <file>] [options of the above]
+  beehive layouts [--layouts <file>]
+  beehive shape <rows> <cols> [--name <name>] [--symmetry <none|60|120|180|mirror>] [--blocks <share>] [--min-len <n>] [--lengths <len:share,...>] [--lines <row,col,diag>] [--rhombus] [--attempts <n>] [--seed <n>]
+  beehive rate [--from <n>] [--count <n>] [--write]
+  beehive booklet <output.pdf> [--from <n>] [--count <n>] [--title <title>] [--plain]
+  beehive ipuz export <output.ipuz> [--index <n> | --code <share-code>]
+  beehive ipuz import <input.ipuz>
+  beehive crossword new <rows> <cols> [--seed <n>] [--clues <file>] [--output <file>]
+  beehive crossword hex [--index <n> | --code <share-code>] [--clues <file>]
+  beehive crossword show <file>
+  beehive bee [--seed <n>] [--letters <centre then 6 letters>] [--answers]
+  beehive search [--theme <name> | --words <word,word,...>] [--themes <file>] [--radius <n>] [--seed <n>]";
+
+// fills counted per puzzle before giving up on ambiguity
+const RATE_NODES: usize = 200_000;
+
+// native entry point, the browser build mounts the app instead
+pub fn run(args: Vec<String>) -> Result<(), String> {
+    let mut args = args.into_iter();
+    match args.next().as_deref() {
+        Some("generate") => generate(args.collect()),
+        Some("render") => render(args.collect()),
+        Some("layouts") => list_layouts(args.collect()),
+        Some("shape") => shape(args.collect()),
+        Some("rate") => rate_corpus(args.collect()),
+        Some("booklet") => write_booklet(args.collect()),
+        Some("ipuz") => ipuz(args.collect()),
+        Some("crossword") => crossword(args.collect()),
+        Some("bee") => spelling_bee(args.collect()),
+        Some("search") => word_search(args.collect()),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+struct Flags {
+    positional: Vec<String>,
+    options: Vec<(String, Option<String>)>,
+}
+
+impl Flags {
+    // `--name value` pairs, or bare `--name` switches when `switches` lists them
+    fn parse(args: Vec<String>, switches: &[&str]) -> Result<Self, String> {
+        let mut positional = vec![];
+        let mut options = vec![];
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                if switches.contains(&name) {
+                    options.push((name.to_string(), None));
+                } else {
+                    let val = args.next().ok_or(format!("missing value for --{}", name))?;
+                    options.push((name.to_string(), Some(val)));
+                }
+            } else {
+                positional.push(arg);
+            }
+        }
+
+        Ok(Self {
+            positional,
+            options,
+        })
+    }
+    fn has(&self, name: &str) -> bool {
+        self.options.iter().any(|(n, _)| n == name)
+    }
+    fn get(&self, name: &str) -> Option<&String> {
+        self.options
+            .iter()
+            .find(|(n, _)| n == name)
+            .and_then(|(_, v)| v.as_ref())
+    }
+    fn get_parsed<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        self.get(name)
+            .map(|v| {
+                v.parse()
+                    .map_err(|_| format!("can't parse --{} {}", name, v))
+            })
+            .transpose()
+    }
+}
+
+fn pick_beehive(flags: &Flags) -> Result<BeehiveSerde, String> {
+    if let Some(code) = flags.get("code") {
+        return share_code::decode(code).map_err(|e| e.to_string());
+    }
+
+    let beehives =
+        read_file("./assets/beehives.json").map_err(|_| "can't read the beehives corpus")?;
+    let index = flags.get_parsed("index")?.unwrap_or(0);
+
+    beehives
+        .get(index)
+        .cloned()
+        .ok_or(format!("no beehive #{} in the corpus", index))
+}
+
+fn generate(args: Vec<String>) -> Result<(), String> {
+    let flags = Flags::parse(args, &["append", "trace", "unique"])?;
+    let registry = registry(&flags)?;
+    let (empty, allow_adding_blacks, locked) =
+        match (flags.positional.as_slice(), flags.get("layout")) {
+            ([rows, cols], None) => {
+                let (empty, allow_adding_blacks) = GridBeehive::layout(
+                    rows.parse().map_err(|_| format!("invalid rows {}", rows))?,
+                    cols.parse().map_err(|_| format!("invalid cols {}", cols))?,
+                );
+                (empty, allow_adding_blacks, None)
+            }
+            ([], Some(name)) => {
+                let layout = registry
+                    .get(name)
+                    .ok_or(format!("no layout named {}", name))?;
+                let locked = Some(layout.locked.clone()).filter(|l| !l.is_empty());
+                (layout.grid.clone(), layout.allow_adding_blacks, locked)
+            }
+            _ => return Err(USAGE.to_string()),
+        };
+    let dictionary = Dictionary::new().map_err(|_| "can't read the dictionaries")?;
+
+    // enter stops the search
+    let cancel = CancelToken::default();
+    let stdin_cancel = cancel.clone();
+    std::thread::spawn(move || {
+        let mut line = String::new();
+        if let Ok(1..) = std::io::stdin().lock().read_line(&mut line) {
+            stdin_cancel.cancel();
+        }
+    });
+
+    let budget = Budget {
+        time: flags
+            .get_parsed::<f64>("timeout")?
+            .map(Duration::from_secs_f64),
+        nodes: flags.get_parsed("nodes")?,
+        cancel: Some(cancel),
+    };
+    let search_options = SearchOptions {
+        seed: flags.get_parsed("seed")?,
+        trace: flags.has("trace"),
+        sampling: match flags.get("sampling").map(|s| s.as_str()) {
+            None | Some("uniform") => Sampling::Uniform,
+            Some(policy @ ("weighted" | "edges")) => {
+                let ranks = WordRanks::new().map_err(|_| "can't read the word frequencies")?;
+                if policy == "weighted" {
+                    Sampling::Weighted(Arc::new(ranks))
+                } else {
+                    Sampling::CommonAtEdges(Arc::new(ranks))
+                }
+            }
+            Some(policy) => {
+                return Err(format!(
+                    "unknown sampling {}, expected uniform, weighted or edges",
+                    policy
+                ))
+            }
+        },
+    };
+    // unique fills make swap puzzles with a single valid arrangement
+    let generate = |budget, search_options: &SearchOptions| {
+        if flags.has("unique") {
+            empty.generate_unique_within(&dictionary, allow_adding_blacks, budget, search_options)
+        } else {
+            empty.generate_within(&dictionary, allow_adding_blacks, budget, search_options)
+        }
+    };
+
+    let (outcome, stats) = if flags.has("threads") || flags.has("count") {
+        let defaults = ParallelOptions::default();
+        let options = ParallelOptions {
+            threads: flags.get_parsed("threads")?.unwrap_or(defaults.threads),
+            count: flags.get_parsed("count")?.unwrap_or(defaults.count),
+            restart_nodes: flags.get_parsed("restart")?,
+        };
+        generate_parallel(&options, budget, &search_options, generate)
+    } else {
+        let (outcome, stats) = generate(budget, &search_options);
+        (outcome.map(|grid| vec![grid]), stats)
+    };
+    println!("{}", stats);
+
+    match outcome {
+        Outcome::Solved(grids) => {
+            for grid in grids {
+                println!("{}", grid);
+                if flags.has("append") {
+                    let layout = (0..grid.rows()).map(|r| grid.get_row(r).unwrap()).collect();
+                    let beehive =
+                        BeehiveSerde::new(layout, None, locked.clone()).with_axes(grid.axes());
+                    append_file(beehive).map_err(|_| "can't append to the beehives corpus")?;
+                }
+            }
+            Ok(())
+        }
+        Outcome::Exhausted => Err("this layout can't be filled".to_string()),
+        Outcome::Timeout => Err("out of budget before finding a beehive".to_string()),
+        Outcome::Cancelled => Err("cancelled".to_string()),
+    }
+}
+
+// the builtin layouts, plus the ones of --layouts <file>
+fn registry(flags: &Flags) -> Result<LayoutRegistry, String> {
+    let mut registry = LayoutRegistry::builtin().clone();
+    if let Some(path) = flags.get("layouts") {
+        registry.load_file(path)?;
+    }
+
+    Ok(registry)
+}
+
+fn list_layouts(args: Vec<String>) -> Result<(), String> {
+    let flags = Flags::parse(args, &[])?;
+    let registry = registry(&flags)?;
+    for layout in registry.layouts() {
+        println!("[{}]\n{}", layout.name, layout.to_text());
+    }
+
+    Ok(())
+}
+
+// prints a generated layout, ready to paste in a --layouts file
+fn shape(args: Vec<String>) -> Result<(), String> {
+    let flags = Flags::parse(args, &["rhombus"])?;
+    let (rows, cols) = match flags.positional.as_slice() {
+        [rows, cols] => (
+            rows.parse()
+                .map_err(|_| format!("can't read rows {}", rows))?,
+            cols.parse()
+                .map_err(|_| format!("can't read cols {}", cols))?,
+        ),
+        _ => return Err(USAGE.to_string()),
+    };
+
+    let defaults = ShapeOptions::default();
+    let mut lengths = defaults.lengths.clone();
+    if let Some(spec) = flags.get("lengths") {
+        for part in spec.split(',') {
+            let parsed = part
+                .split_once(':')
+                .and_then(|(len, share)| Some((len.parse().ok()?, share.parse().ok()?)));
+            let (len, share) = parsed.ok_or(format!("can't read length share {}", part))?;
+            lengths.insert(len, share);
+        }
+    }
+    let options = ShapeOptions {
+        rows,
+        cols,
+        symmetry: match flags.get("symmetry") {
+            Some(symmetry) => symmetry.parse::<Symmetry>()?,
+            None => defaults.symmetry,
+        },
+        axes: match flags.get("lines") {
+            Some(axes) => axes.parse()?,
+            None => defaults.axes,
+        },
+        hexagon: !flags.has("rhombus"),
+        blocks: flags.get_parsed("blocks")?.unwrap_or(defaults.blocks),
+        min_len: flags.get_parsed("min-len")?.unwrap_or(defaults.min_len),
+        lengths,
+        attempts: flags.get_parsed("attempts")?.unwrap_or(defaults.attempts),
+        seed: flags.get_parsed("seed")?,
+    };
+
+    // the length buckets are checked against the common words the generator fills from
+    let words = std::fs::read_to_string("dictionaries/english/most_frequent_words")
+        .map_err(|_| "can't read the dictionaries")?;
+    let dictionary = Dictionary::from_words(words.lines().map(|w| w.to_string()).collect());
+    let layout = generate_shape(&options, &dictionary)?;
+    let name = flags.get("name").cloned().unwrap_or(layout.name.clone());
+    println!("[{}]\n{}", name, layout.to_text());
+
+    Ok(())
+}
+
+fn render(args: Vec<String>) -> Result<(), String> {
+    let flags = Flags::parse(args, &["swap", "plain"])?;
+    let (format, output) = match flags.positional.as_slice() {
+        [format, output] => (format.as_str(), output.as_str()),
+        _ => return Err(USAGE.to_string()),
+    };
+
+    let beehive = pick_beehive(&flags)?;
+    let drawing = if flags.has("swap") {
+        let swap: BeehiveSwap = beehive.into();
+        HexDrawing::from(&swap)
+    } else {
+        let grid: GridBeehive = beehive.into();
+        HexDrawing::from(&grid)
+    };
+
+    let mut options = RenderOptions {
+        colored: !flags.has("plain"),
+        ..RenderOptions::default()
+    };
+    if let Some(size) = flags.get_parsed::<f64>("size")? {
+        if size <= 0.0 || !size.is_finite() {
+            return Err(format!("invalid size {}, expected a positive number", size));
+        }
+        options.cell_size = size;
+    }
+
+    let bytes = match format {
+        "svg" => drawing.to_svg(&options).into_bytes(),
+        "png" => drawing.to_png(&options),
+        _ => return Err(format!("unknown format {}, expected svg or png", format)),
+    };
+    std::fs::write(output, bytes).map_err(|e| format!("can't write {}: {}", output, e))?;
+    println!("beehive rendered to {}", output);
+
+    Ok(())
+}
+
+// rates the corpus, --write stores the ratings with the scrambles they were made on
+fn rate_corpus(args: Vec<String>) -> Result<(), String> {
+    let flags = Flags::parse(args, &["write"])?;
+    if !flags.positional.is_empty() {
+        return Err(USAGE.to_string());
+    }
+
+    // counting fills only goes through the common words
+    let words = std::fs::read_to_string("dictionaries/english/most_frequent_words")
+        .map_err(|_| "can't read the dictionaries")?;
+    let dictionary = Dictionary::from_words(words.lines().map(|w| w.to_string()).collect());
+    let ranks = WordRanks::new().map_err(|_| "can't read the word frequencies")?;
+
+    let path = "./assets/beehives.json";
+    let mut beehives = read_file(path).map_err(|_| "can't read the beehives corpus")?;
+    let from = flags.get_parsed("from")?.unwrap_or(0);
+    let count = flags.get_parsed("count")?.unwrap_or(beehives.len());
+
+    for (index, beehive) in beehives.iter_mut().enumerate().skip(from).take(count) {
+        let swap: BeehiveSwap = beehive.clone().into();
+        let budget = Budget {
+            nodes: Some(RATE_NODES),
+            ..Default::default()
+        };
+        let rating = rate(&swap, &ranks, &dictionary, budget);
+        println!("#{} {}", index, rating);
+        *beehive = BeehiveSerde::from(swap).with_rating(rating);
+    }
+
+    if flags.has("write") {
+        let json = serde_json::to_string(&beehives).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| format!("can't write {}: {}", path, e))?;
+    }
+
+    Ok(())
+}
+
+fn write_booklet(args: Vec<String>) -> Result<(), String> {
+    let flags = Flags::parse(args, &["plain"])?;
+    let output = match flags.positional.as_slice() {
+        [output] => output.as_str(),
+        _ => return Err(USAGE.to_string()),
+    };
+
+    let beehives =
+        read_file("./assets/beehives.json").map_err(|_| "can't read the beehives corpus")?;
+    let from = flags.get_parsed("from")?.unwrap_or(0);
+    let count = flags.get_parsed("count")?.unwrap_or(10);
+    let beehives: Vec<BeehiveSerde> = beehives.into_iter().skip(from).take(count).collect();
+    if beehives.is_empty() {
+        return Err(format!("no beehive left in the corpus after #{}", from));
+    }
+
+    let mut options = BookletOptions {
+        colored: !flags.has("plain"),
+        ..BookletOptions::default()
+    };
+    if let Some(title) = flags.get("title") {
+        options.title = title.clone();
+    }
+
+    std::fs::write(output, booklet(&beehives, &options))
+        .map_err(|e| format!("can't write {}: {}", output, e))?;
+    println!("{} beehives written to {}", beehives.len(), output);
+
+    Ok(())
+}
+
+fn ipuz(args: Vec<String>) -> Result<(), String> {
+    let flags = Flags::parse(args, &[])?;
+    match flags.positional.as_slice() {
+        [action, output] if action == "export" => {
+            let grid: GridBeehive = pick_beehive(&flags)?.into();
+            let json =
+                serde_json::to_string_pretty(&beehive_to_ipuz(&grid)).map_err(|e| e.to_string())?;
+            std::fs::write(output, json).map_err(|e| format!("can't write {}: {}", output, e))?;
+            println!("beehive exported to {}", output);
+        }
+        [action, input] if action == "import" => {
+            let contents = std::fs::read_to_string(input)
+                .map_err(|e| format!("can't read {}: {}", input, e))?;
+            let ipuz = from_ipuz(&contents).map_err(|e| e.to_string())?;
+            match ipuz.grid {
+                IpuzGrid::Square(grid) => println!("{}", grid),
+                IpuzGrid::Hex(grid) => println!("{}", grid),
+            }
+            for clue in ipuz.clues {
+                println!("{} {}: {}", clue.number, clue.direction, clue.clue);
+            }
+        }
+        _ => return Err(USAGE.to_string()),
+    }
+
+    Ok(())
+}
+
+// the words without a clue in the database are left for writing by hand
+fn read_clues(flags: &Flags) -> Result<ClueDatabase, String> {
+    let path = flags
+        .get("clues")
+        .map(|p| p.as_str())
+        .unwrap_or("assets/clues.txt");
+    let text = std::fs::read_to_string(path).map_err(|e| format!("can't read {}: {}", path, e))?;
+    parse_clue_database(&text).map_err(|e| format!("{}, {}", path, e))
+}
+
+fn report_missing(missing: &[String]) {
+    if !missing.is_empty() {
+        eprintln!("no clue for {}", missing.join(", "));
+    }
+}
+
+// new crosswords take their clues from the database, the rest are written in the
+// file by hand
+fn crossword(args: Vec<String>) -> Result<(), String> {
+    let flags = Flags::parse(args, &[])?;
+    match flags.positional.as_slice() {
+        [action, rows, cols] if action == "new" => {
+            let rows = rows.parse().map_err(|_| format!("invalid rows {}", rows))?;
+            let cols = cols.parse().map_err(|_| format!("invalid cols {}", cols))?;
+            // answers come from the common words, the ones players know
+            let words = std::fs::read_to_string("dictionaries/english/most_frequent_words")
+                .map_err(|_| "can't read the dictionaries")?;
+            let dictionary = Dictionary::from_words(words.lines().map(|w| w.to_string()).collect());
+            let options = SearchOptions {
+                seed: flags.get_parsed("seed")?,
+                ..Default::default()
+            };
+            let grid = match Grid::new(rows, cols)
+                .generate_within(&dictionary, Budget::default(), &options)
+                .0
+            {
+                Outcome::Solved(grid) => grid,
+                _ => return Err("couldn't fill a grid of that size".to_string()),
+            };
+
+            let mut crossword = Crossword::new(grid);
+            if flags.get("clues").is_some() {
+                report_missing(&crossword.clue_from(&read_clues(&flags)?));
+            }
+            match flags.get("output") {
+                Some(output) => {
+                    std::fs::write(output, crossword.to_text())
+                        .map_err(|e| format!("can't write {}: {}", output, e))?;
+                    println!("crossword written to {}", output);
+                }
+                None => print!("{}", crossword.to_text()),
+            }
+        }
+        [action] if action == "hex" => {
+            let beehive: GridBeehive = pick_beehive(&flags)?.into();
+            let mut crossword = Crossword::new(beehive);
+            report_missing(&crossword.clue_from(&read_clues(&flags)?));
+            print!("{}", crossword);
+        }
+        [action, input] if action == "show" => {
+            let text = std::fs::read_to_string(input)
+                .map_err(|e| format!("can't read {}: {}", input, e))?;
+            let crossword = parse_crossword(&text).map_err(|e| format!("{}, {}", input, e))?;
+            print!("{}", crossword);
+        }
+        _ => return Err(USAGE.to_string()),
+    }
+
+    Ok(())
+}
+
+// random letters unless some are given, the first of them goes in the centre
+fn spelling_bee(args: Vec<String>) -> Result<(), String> {
+    let flags = Flags::parse(args, &["answers"])?;
+    let words = std::fs::read_to_string("dictionaries/english/most_frequent_words")
+        .map_err(|_| "can't read the dictionaries")?;
+    let dictionary = Dictionary::from_words(words.lines().map(|w| w.to_string()).collect());
+    let bee = match flags.get("letters") {
+        Some(letters) => {
+            let mut chars = letters.chars();
+            let centre = chars.next().ok_or("no letters given")?;
+            SpellingBee::new(centre, chars.as_str(), &dictionary)?
+        }
+        None => SpellingBee::generate(&dictionary, flags.get_parsed("seed")?)
+            .ok_or("no letters with a pangram and enough answers")?,
+    };
+
+    print!("{}", bee);
+    if flags.has("answers") {
+        for word in bee.answers() {
+            match bee.is_pangram(word) {
+                true => println!("{} (pangram)", word),
+                false => println!("{}", word),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// the words are hidden in a hexagon, a theme from the file unless they are given
+fn word_search(args: Vec<String>) -> Result<(), String> {
+    let flags = Flags::parse(args, &[])?;
+    let words: Vec<String> = match flags.get("words") {
+        Some(words) => parse_words(words)?,
+        None => {
+            let path = flags
+                .get("themes")
+                .map(|p| p.as_str())
+                .unwrap_or("assets/themes.txt");
+            let text =
+                std::fs::read_to_string(path).map_err(|e| format!("can't read {}: {}", path, e))?;
+            let themes = parse_themes(&text)?;
+            let theme = match flags.get("theme") {
+                Some(name) => themes.into_iter().find(|(n, _)| n == name),
+                None => themes.into_iter().next(),
+            };
+            theme.ok_or("no such theme")?.1
+        }
+    };
+    let radius = flags.get_parsed("radius")?.unwrap_or(4);
+    let dictionary_words = std::fs::read_to_string("dictionaries/english/most_frequent_words")
+        .map_err(|_| "can't read the dictionaries")?;
+    let dictionary =
+        Dictionary::from_words(dictionary_words.lines().map(|w| w.to_string()).collect());
+
+    let search = generate_word_search(
+        &GridBeehive::new_hexagon(radius),
+        &words,
+        &dictionary,
+        flags.get_parsed("seed")?,
+    )?;
+    print!("{}", search);
+
+    Ok(())
+}
@@ -0,0 +1,693 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::board::{Board, Cell, Kind, Slot};
+use crate::grid::Grid;
+use crate::grid_beehive::GridBeehive;
+use crate::ipuz::{direction_key, direction_name};
+
+// a numbered slot, with the answer of the fill and the clue shown for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub number: usize,
+    pub slot: Slot,
+    pub answer: String,
+    pub clue: String,
+}
+
+impl Entry {
+    pub fn kind(&self) -> &Kind {
+        self.slot.line.kind()
+    }
+    // the letter count printed after a clue, like "(5)"
+    pub fn enumeration(&self) -> String {
+        format!("({})", self.slot.cells.len())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrosswordError {
+    // 1-based, like editors show it
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for CrosswordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+// a filled board read as a crossword, the entries sorted by direction then number
+#[derive(Debug, Clone)]
+pub struct Crossword<B: Board> {
+    board: B,
+    entries: Vec<Entry>,
+}
+
+impl<B: Board> Crossword<B> {
+    // standard numbering: row by row, one number per cell starting a slot
+    pub fn new(board: B) -> Self {
+        let slots = board.slots();
+        let mut starts: Vec<Cell> = slots.iter().map(|s| s.cells[0]).collect();
+        starts.sort_by_key(|c| (c.row(), c.col()));
+        starts.dedup();
+
+        let mut entries: Vec<Entry> = slots
+            .into_iter()
+            .map(|slot| Entry {
+                number: starts.iter().position(|c| c == &slot.cells[0]).unwrap() + 1,
+                answer: slot
+                    .cells
+                    .iter()
+                    .map(|c| *board.get_cell(c.row(), c.col()).unwrap())
+                    .collect(),
+                clue: String::new(),
+                slot,
+            })
+            .collect();
+        entries.sort_by_key(|e| {
+            (
+                board.axes().kinds().iter().position(|k| k == e.kind()),
+                e.number,
+            )
+        });
+
+        Self { board, entries }
+    }
+
+    pub fn board(&self) -> &B {
+        &self.board
+    }
+    pub fn entries(&self) -> &Vec<Entry> {
+        &self.entries
+    }
+    pub fn entry(&self, number: usize, kind: &Kind) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|e| e.number == number && e.kind() == kind)
+    }
+    // the entry of that direction going through a cell
+    pub fn entry_at(&self, cell: &Cell, kind: &Kind) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|e| e.kind() == kind && e.slot.cells.contains(cell))
+    }
+    // the number printed in the corner of a cell
+    pub fn number_at(&self, cell: &Cell) -> Option<usize> {
+        self.entries
+            .iter()
+            .find(|e| &e.slot.cells[0] == cell)
+            .map(|e| e.number)
+    }
+    pub fn solution(&self, cell: &Cell) -> Option<char> {
+        self.board
+            .get_cell(cell.row(), cell.col())
+            .filter(|c| **c != '_')
+            .cloned()
+    }
+
+    pub fn set_clue(&mut self, number: usize, kind: &Kind, clue: &str) -> Result<(), String> {
+        let entry = self
+            .entries
+            .iter_mut()
+            .find(|e| e.number == number && e.kind() == kind)
+            .ok_or(format!("no {} {}", number, direction_key(kind)))?;
+        entry.clue = clue.to_string();
+
+        Ok(())
+    }
+    // looks every answer up, returns the ones the database has no clue for
+    pub fn clue_from(&mut self, database: &ClueDatabase) -> Vec<String> {
+        let mut missing = vec![];
+        for entry in self.entries.iter_mut() {
+            match database.get(&entry.answer) {
+                Some(clue) => entry.clue = clue.to_string(),
+                None => missing.push(entry.answer.clone()),
+            }
+        }
+        missing.sort();
+        missing.dedup();
+
+        missing
+    }
+
+    // typed letters that don't match the solution, empty cells aren't wrong
+    pub fn wrong_cells(&self, typed: &HashMap<Cell, char>) -> Vec<Cell> {
+        let mut wrong: Vec<Cell> = typed
+            .iter()
+            .filter(|(cell, letter)| self.solution(cell).is_some_and(|s| s != **letter))
+            .map(|(cell, _)| *cell)
+            .collect();
+        wrong.sort_by_key(|c| (c.row(), c.col()));
+
+        wrong
+    }
+    // the wrong letters of a single entry, for checking one slot at a time
+    pub fn check_entry(&self, entry: &Entry, typed: &HashMap<Cell, char>) -> Vec<Cell> {
+        self.wrong_cells(typed)
+            .into_iter()
+            .filter(|cell| entry.slot.cells.contains(cell))
+            .collect()
+    }
+    pub fn reveal_entry(&self, entry: &Entry, typed: &mut HashMap<Cell, char>) {
+        for (cell, letter) in entry.slot.cells.iter().zip(entry.answer.chars()) {
+            typed.insert(*cell, letter);
+        }
+    }
+    pub fn is_solved(&self, typed: &HashMap<Cell, char>) -> bool {
+        self.entries
+            .iter()
+            .flat_map(|e| e.slot.cells.iter())
+            .all(|cell| typed.get(cell).cloned() == self.solution(cell))
+    }
+
+    // the rows of the solution then one clue per line, as the crossword file reads
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for row in self.board.to_rows() {
+            let cells: Vec<String> = row
+                .chars()
+                .map(|c| match c {
+                    '_' => "#".to_string(),
+                    l => l.to_string(),
+                })
+                .collect();
+            text.push_str(&format!("{}\n", cells.join(" ")));
+        }
+        for entry in &self.entries {
+            let line = format!(
+                "{} {} = {}",
+                entry.number,
+                direction_key(entry.kind()),
+                entry.clue
+            );
+            text.push_str(&format!("{}\n", line.trim_end()));
+        }
+
+        text
+    }
+}
+
+impl<B: Board> fmt::Display for Crossword<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = self.board.axes().contains(&Kind::Diag);
+        for r in 0..self.board.rows() {
+            // hex rows shift by half a cell, like the beehive is drawn
+            if hex {
+                write!(f, "{}", "  ".repeat(r))?;
+            }
+            for c in 0..self.board.cols() {
+                let cell = Cell::new(r, c);
+                match (self.solution(&cell), self.number_at(&cell)) {
+                    (None, _) => write!(f, " ## ")?,
+                    (Some(_), Some(n)) => write!(f, "{:>3} ", n)?,
+                    (Some(_), None) => write!(f, "  . ")?,
+                }
+            }
+            writeln!(f)?;
+        }
+
+        let mut kind = None;
+        for entry in &self.entries {
+            if kind != Some(entry.kind()) {
+                kind = Some(entry.kind());
+                writeln!(f, "{}", direction_name(entry.kind()))?;
+            }
+            writeln!(
+                f,
+                "{:>3}. {} {}",
+                entry.number,
+                entry.clue,
+                entry.enumeration()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// a square crossword: the solution rows with # for blocks, then clues like
+// `1 across = Feline pet`. clues left out stay empty, for filling in by hand
+pub fn parse_crossword(text: &str) -> Result<Crossword<Grid>, CrosswordError> {
+    let mut rows: Vec<Vec<char>> = vec![];
+    let mut clues = vec![];
+    for (i, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        let error = |message: String| CrosswordError {
+            line: i + 1,
+            message,
+        };
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some((key, clue)) = line.split_once('=') {
+            let parsed = key.split_once(' ').and_then(|(number, kind)| {
+                let kind = [Kind::Row, Kind::Col]
+                    .into_iter()
+                    .find(|k| direction_key(k) == kind.trim())?;
+                Some((number.parse::<usize>().ok()?, kind))
+            });
+            let (number, kind) = parsed.ok_or(error(format!(
+                "can't read {}, expected a number then across or down",
+                key.trim()
+            )))?;
+            clues.push((i + 1, number, kind, clue.trim().to_string()));
+            continue;
+        }
+        if !clues.is_empty() {
+            return Err(error("the rows go before the clues".to_string()));
+        }
+
+        let mut row = vec![];
+        for c in line.chars().filter(|c| !c.is_whitespace()) {
+            row.push(match c {
+                '#' => '_',
+                l if l.is_ascii_alphabetic() => l.to_ascii_lowercase(),
+                l => return Err(error(format!("unexpected {}, expected # or a letter", l))),
+            });
+        }
+        if rows.first().is_some_and(|first| first.len() != row.len()) {
+            return Err(error(format!(
+                "the rows aren't all {} cells long",
+                rows[0].len()
+            )));
+        }
+        rows.push(row);
+    }
+
+    let cols = rows.first().map(|r| r.len()).unwrap_or(0);
+    if cols == 0 {
+        return Err(CrosswordError {
+            line: 1,
+            message: "a crossword needs rows".to_string(),
+        });
+    }
+    let mut grid = Grid::new(rows.len(), cols);
+    for (r, row) in rows.iter().enumerate() {
+        for (c, val) in row.iter().enumerate() {
+            grid.set_cell(r, c, *val);
+        }
+    }
+
+    let mut crossword = Crossword::new(grid);
+    for (line, number, kind, clue) in clues {
+        crossword
+            .set_clue(number, &kind, &clue)
+            .map_err(|message| CrosswordError { line, message })?;
+    }
+
+    Ok(crossword)
+}
+
+// clues keyed by answer, so any filled board can be clued without numbering it
+// by hand first
+#[derive(Debug, Clone, Default)]
+pub struct ClueDatabase {
+    clues: HashMap<String, String>,
+}
+
+impl ClueDatabase {
+    pub fn get(&self, word: &str) -> Option<&str> {
+        self.clues.get(word).map(|c| c.as_str())
+    }
+    pub fn words(&self) -> usize {
+        self.clues.len()
+    }
+}
+
+// one `word = clue` per line, `;` starts a comment. a word given twice keeps the
+// later clue
+pub fn parse_clue_database(text: &str) -> Result<ClueDatabase, CrosswordError> {
+    let mut clues = HashMap::new();
+    for (i, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        let error = |message: String| CrosswordError {
+            line: i + 1,
+            message,
+        };
+
+        let (word, clue) = line
+            .split_once('=')
+            .ok_or(error("expected `word = clue`".to_string()))?;
+        let word = word.trim().to_lowercase();
+        if word.is_empty() || !word.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(error(format!("{} isn't a word", word)));
+        }
+        if clue.trim().is_empty() {
+            return Err(error(format!("no clue for {}", word)));
+        }
+        clues.insert(word, clue.trim().to_string());
+    }
+
+    Ok(ClueDatabase { clues })
+}
+
+// the browser build plays the crossword shipped in the assets
+pub async fn fetch_crossword(_c: ()) -> Result<Crossword<Grid>, String> {
+    let text = reqwasm::http::Request::get("/assets/crossword.txt")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    parse_crossword(&text).map_err(|e| e.to_string())
+}
+
+// a random beehive of the stored ones, clued from the shipped database
+pub async fn fetch_hex_crossword(_c: ()) -> Result<Crossword<GridBeehive>, String> {
+    let text = reqwasm::http::Request::get("/assets/clues.txt")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+    let database = parse_clue_database(&text).map_err(|e| e.to_string())?;
+    let beehive: GridBeehive = crate::beehive_serde::fetch_beehive(None)
+        .await
+        .map_err(|_| "couldn't load a beehive".to_string())?
+        .into();
+
+    let mut crossword = Crossword::new(beehive);
+    crossword.clue_from(&database);
+
+    Ok(crossword)
+}
+
+pub mod ui {
+    use leptos::*;
+
+    use super::*;
+
+    // square boards draw as a grid, hex ones as rows shifted by half a cell
+    #[component]
+    pub fn CrosswordComponent<B: Board + 'static>(
+        crossword: Crossword<B>,
+        #[prop(optional)] hex: bool,
+    ) -> impl IntoView {
+        let kinds = crossword.board().axes().kinds();
+        let crossword = store_value(crossword);
+        let (typed, set_typed) = create_signal(HashMap::<Cell, char>::new());
+        // the cell the next letter goes in and the direction it moves along
+        let (selected, set_selected) = create_signal::<Option<(Cell, Kind)>>(None);
+        let (wrong, set_wrong) = create_signal::<Vec<Cell>>(vec![]);
+        let solved = move || crossword.with_value(|cw| typed.with(|t| cw.is_solved(t)));
+        let selected_entry = move || {
+            selected.get().and_then(|(cell, kind)| {
+                crossword.with_value(|cw| cw.entry_at(&cell, &kind).cloned())
+            })
+        };
+
+        // clicking the selected cell again cycles through its other directions
+        let cycle = store_value(kinds.clone());
+        let select = move |cell: Cell| {
+            let has = |kind: &Kind| crossword.with_value(|cw| cw.entry_at(&cell, kind).is_some());
+            let kind = match selected.get() {
+                Some((c, kind)) if c == cell => cycle.with_value(|cycle| {
+                    let at = cycle.iter().position(|k| k == &kind).unwrap_or(0);
+                    (1..=cycle.len())
+                        .map(|i| cycle[(at + i) % cycle.len()])
+                        .find(has)
+                        .unwrap_or(kind)
+                }),
+                Some((_, kind)) if has(&kind) => kind,
+                _ => cycle.with_value(|cycle| cycle.iter().cloned().find(has).unwrap_or(Kind::Row)),
+            };
+            set_selected.set(Some((cell, kind)));
+        };
+        // the neighbour along the selected entry, forward or back
+        let step = move |forward: bool| {
+            if let (Some((cell, kind)), Some(entry)) = (selected.get(), selected_entry()) {
+                let cells = &entry.slot.cells;
+                let i = cells.iter().position(|c| c == &cell).unwrap();
+                let next = match forward {
+                    true => cells.get(i + 1).cloned(),
+                    false => i.checked_sub(1).map(|i| cells[i]),
+                };
+                if let Some(next) = next {
+                    set_selected.set(Some((next, kind)));
+                }
+            }
+        };
+        let on_key = move |ev: ev::KeyboardEvent| {
+            let Some((cell, _)) = selected.get() else {
+                return;
+            };
+            let key = ev.key();
+            match key.as_str() {
+                "Backspace" => {
+                    if typed.with(|t| t.contains_key(&cell)) {
+                        set_typed.update(|t| {
+                            t.remove(&cell);
+                        });
+                    } else {
+                        step(false);
+                    }
+                }
+                _ => {
+                    let mut chars = key.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(l), None) if l.is_ascii_alphabetic() => {
+                            set_typed.update(|t| {
+                                t.insert(cell, l.to_ascii_lowercase());
+                            });
+                            set_wrong.update(|w| w.retain(|c| c != &cell));
+                            step(true);
+                        }
+                        _ => return,
+                    }
+                }
+            }
+            ev.prevent_default();
+        };
+        let in_selected_entry =
+            move |cell: &Cell| selected_entry().is_some_and(|e| e.slot.cells.contains(cell));
+
+        let (rows, cols) = crossword.with_value(|cw| (cw.board().rows(), cw.board().cols()));
+        let draw_cell = move |cell: Cell| {
+            let (solution, number) =
+                crossword.with_value(|cw| (cw.solution(&cell), cw.number_at(&cell)));
+            let class = if hex { "cell" } else { "square" };
+            match solution {
+                None if hex => view! { <div class="empty-cell" /> },
+                None => view! { <div class="block" /> },
+                Some(_) => view! {
+                    <div
+                        class=class
+                        class:is-selected = move || selected.get().is_some_and(|(c, _)| c == cell)
+                        class:is-active = move || in_selected_entry(&cell)
+                        class:is-wrong = move || wrong.with(|w| w.contains(&cell))
+                        on:click=move |_| select(cell)
+                    >
+                        <span class="number">{number}</span>
+                        {move || typed.with(|t| t.get(&cell).map(|l| l.to_uppercase().to_string()))}
+                    </div>
+                },
+            }
+        };
+        let board = if hex {
+            view! {
+                <div
+                    class="crossword-container hex"
+                    tabindex="0"
+                    on:keydown=on_key
+                    style:grid-template-columns = format!("repeat({}, 0.75em)", 2 * cols + rows - 1)
+                >
+                    {(0..rows).map(|r| {
+                        let offsets_before = (0..r).map(|_| view! { <div class="offset" /> }).collect_view();
+                        let cells = (0..cols).map(|c| draw_cell(Cell::new(r, c))).collect_view();
+                        let offsets_after = (0..(rows - r - 1)).map(|_| view! { <div class="offset" /> }).collect_view();
+
+                        vec![offsets_before, cells, offsets_after].into_iter().collect_view()
+                    }).collect_view()}
+                </div>
+            }
+        } else {
+            view! {
+                <div
+                    class="crossword-container"
+                    tabindex="0"
+                    on:keydown=on_key
+                    style:grid-template-columns = format!("repeat({}, 1.5em)", cols)
+                >
+                    {(0..rows).flat_map(|r| (0..cols).map(move |c| Cell::new(r, c))).map(draw_cell).collect_view()}
+                </div>
+            }
+        };
+
+        let clue_list = move |kind: Kind| {
+            let entries: Vec<Entry> = crossword.with_value(|cw| {
+                cw.entries()
+                    .iter()
+                    .filter(|e| e.kind() == &kind)
+                    .cloned()
+                    .collect()
+            });
+            view! {
+                <div class="clues">
+                    <h3>{direction_name(&kind)}</h3>
+                    {entries.into_iter().map(|entry| {
+                        let start = entry.slot.cells[0];
+                        let cells = entry.slot.cells.clone();
+                        let active = move || {
+                            selected.get().is_some_and(|(at, k)| k == kind && cells.contains(&at))
+                        };
+                        view! {
+                            <div
+                                class="clue"
+                                class:is-active=active
+                                on:click=move |_| set_selected.set(Some((start, kind)))
+                            >
+                                {entry.number} ". " {entry.clue.clone()} " " {entry.enumeration()}
+                            </div>
+                        }
+                    }).collect_view()}
+                </div>
+            }
+        };
+
+        view! {
+            <div class="crossword">
+                {board}
+                {kinds.into_iter().map(clue_list).collect_view()}
+            </div>
+            <button on:click=move |_| {
+                if let Some(entry) = selected_entry() {
+                    let cells = crossword.with_value(|cw| typed.with(|t| cw.check_entry(&entry, t)));
+                    set_wrong.update(|w| w.extend(cells));
+                }
+            }>"Check word"</button>
+            <button on:click=move |_| {
+                if let Some(entry) = selected_entry() {
+                    set_typed.update(|t| crossword.with_value(|cw| cw.reveal_entry(&entry, t)));
+                    set_wrong.update(|w| w.retain(|c| !entry.slot.cells.contains(c)));
+                }
+            }>"Reveal word"</button>
+            <button on:click=move |_| {
+                set_wrong.set(crossword.with_value(|cw| typed.with(|t| cw.wrong_cells(t))))
+            }>"Check all"</button>
+            {move || solved().then(|| view! { <div>"Solved!"</div> })}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{parse_clue_database, parse_crossword, Crossword};
+    use crate::board::{Board, Cell, Kind};
+    use crate::grid_beehive::GridBeehive;
+
+    const TEXT: &str = "; a 3x4 with a block
+c a t s
+o # o #
+w e e d
+1 across = Feline pets
+1 down = Dairy animal
+3 across = Garden pest
+";
+
+    #[test]
+    fn numbered_entries() {
+        let crossword = parse_crossword(TEXT).unwrap();
+        println!("{}", crossword);
+        let numbers: Vec<(usize, Kind, usize)> = crossword
+            .entries()
+            .iter()
+            .map(|e| (e.number, *e.kind(), e.slot.cells.len()))
+            .collect();
+        assert_eq!(
+            numbers,
+            vec![
+                (1, Kind::Row, 4),
+                (3, Kind::Row, 4),
+                (1, Kind::Col, 3),
+                (2, Kind::Col, 3),
+            ]
+        );
+        assert_eq!(crossword.entry(2, &Kind::Col).unwrap().answer, "toe");
+        assert_eq!(crossword.number_at(&Cell::new(2, 0)), Some(3));
+        assert_eq!(crossword.number_at(&Cell::new(2, 1)), None);
+
+        // 2 down is left for filling in by hand
+        let mut again = parse_crossword(&crossword.to_text()).unwrap();
+        assert_eq!(again.to_text(), crossword.to_text());
+        assert!(again.set_clue(2, &Kind::Col, "Foot digit").is_ok());
+        assert!(again.set_clue(2, &Kind::Row, "Nothing there").is_err());
+
+        assert_eq!(parse_crossword("ab\n1 sideways = x").unwrap_err().line, 2);
+        assert!(parse_crossword("ab\nabc").is_err());
+    }
+
+    #[test]
+    fn check_answers() {
+        let crossword = parse_crossword(TEXT).unwrap();
+        let mut typed: HashMap<Cell, char> = HashMap::new();
+        for entry in crossword.entries() {
+            for (cell, letter) in entry.slot.cells.iter().zip(entry.answer.chars()) {
+                typed.insert(*cell, letter);
+            }
+        }
+        assert!(crossword.is_solved(&typed));
+        assert!(crossword.wrong_cells(&typed).is_empty());
+
+        typed.insert(Cell::new(0, 1), 'u');
+        typed.remove(&Cell::new(2, 3));
+        assert_eq!(crossword.wrong_cells(&typed), vec![Cell::new(0, 1)]);
+        assert!(!crossword.is_solved(&typed));
+
+        // 3 across is only missing a letter, 1 across has a wrong one
+        let cats = crossword.entry(1, &Kind::Row).unwrap();
+        let weed = crossword.entry(3, &Kind::Row).unwrap();
+        assert_eq!(crossword.check_entry(cats, &typed), vec![Cell::new(0, 1)]);
+        assert!(crossword.check_entry(weed, &typed).is_empty());
+        crossword.reveal_entry(cats, &mut typed);
+        crossword.reveal_entry(weed, &mut typed);
+        assert!(crossword.is_solved(&typed));
+    }
+
+    #[test]
+    fn hex_crossword_from_database() {
+        let database = parse_clue_database(
+            "; two letter words
+ab = Muscles, for short
+ab = Gym muscles
+cd = Disc
+ac = Cooling, briefly
+",
+        )
+        .unwrap();
+        assert_eq!(database.words(), 3);
+        assert_eq!(database.get("ab"), Some("Gym muscles"));
+        assert_eq!(parse_clue_database("ab\n").unwrap_err().line, 1);
+        assert_eq!(parse_clue_database("\nab =").unwrap_err().line, 2);
+
+        let mut beehive = GridBeehive::new(2, 2);
+        beehive.set_row(0, "ab".to_string());
+        beehive.set_row(1, "cd".to_string());
+        let mut crossword = Crossword::new(beehive);
+        println!("{}", crossword);
+        let kinds: Vec<Kind> = crossword.entries().iter().map(|e| *e.kind()).collect();
+        assert_eq!(
+            kinds,
+            vec![Kind::Row, Kind::Row, Kind::Col, Kind::Col, Kind::Diag]
+        );
+
+        let missing = crossword.clue_from(&database);
+        assert_eq!(crossword.entry(1, &Kind::Row).unwrap().clue, "Gym muscles");
+        assert_eq!(
+            crossword.entry(1, &Kind::Col).unwrap().clue,
+            "Cooling, briefly"
+        );
+        assert_eq!(missing.len(), 2);
+        assert!(missing.contains(&"bd".to_string()));
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    collections::HashSet,
     fs::File,
     io::{BufReader, Read, Write},
     result::Result,
@@ -62,6 +63,27 @@ impl Dictionary {
         Ok(dictionary)
     }
 
+    // in-memory dictionary without forbidden tuples, for small word lists
+    pub fn from_words(common_words: Vec<String>) -> Self {
+        Self {
+            all_words: common_words.clone(),
+            common_words,
+            forbidden_tuples: vec![],
+            forbidden_starts: vec![],
+            forbidden_ends: vec![],
+        }
+    }
+
+    // common words per length, a layout needs as many as it has slots of that length
+    pub fn count_by_length(&self) -> std::collections::HashMap<usize, usize> {
+        let mut counts = std::collections::HashMap::new();
+        for word in &self.common_words {
+            *counts.entry(word.len()).or_default() += 1;
+        }
+
+        counts
+    }
+
     fn _gen_forbidden_tuples(self) -> Result<(), ()> {
         let mut tuples: Vec<String> = vec![];
         for a in "abcdefghijklmnopqrstuvwxyz".chars() {
@@ -255,6 +277,42 @@ impl Dictionary {
     }
 }
 
+// the forbidden tuples, starts and ends hashed, for checking a whole word list
+// against them the way has_forbidden_tuples checks a line
+pub struct ForbiddenParts<'a> {
+    tuples: HashSet<&'a str>,
+    starts: HashSet<&'a str>,
+    ends: HashSet<&'a str>,
+}
+
+impl<'a> ForbiddenParts<'a> {
+    pub fn new(dictionary: &'a Dictionary) -> Self {
+        let set = |list: &'a Vec<String>| list.iter().map(|s| s.as_str()).collect();
+        Self {
+            tuples: set(&dictionary.forbidden_tuples),
+            starts: set(&dictionary.forbidden_starts),
+            ends: set(&dictionary.forbidden_ends),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tuples.is_empty() && self.starts.is_empty() && self.ends.is_empty()
+    }
+
+    // a complete word, pairs and trios anywhere, starts and ends past 2 letters
+    pub fn spoils(&self, word: &str) -> bool {
+        let tuple = |len: usize| {
+            (0..(word.len() + 1).saturating_sub(len))
+                .any(|i| self.tuples.contains(&word[i..i + len]))
+        };
+        tuple(2)
+            || tuple(3)
+            || (word.len() > 2
+                && (self.starts.contains(&word[..2])
+                    || self.ends.contains(&word[word.len() - 2..])))
+    }
+}
+
 fn match_pattern(word: &String, pattern: &String) -> bool {
     if word.len() != pattern.len() {
         return false;
@@ -272,6 +330,120 @@ fn match_pattern(word: &String, pattern: &String) -> bool {
     result
 }
 
+// membership only word list small enough for the browser: the words of each
+// length are sorted and packed into a single string
+#[derive(Debug, Clone, Default)]
+pub struct WordSet {
+    by_length: Vec<String>,
+}
+
+impl WordSet {
+    pub fn from_words<'a>(words: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut buckets: Vec<Vec<&str>> = vec![];
+        for word in words {
+            let word = word.trim();
+            if word.is_empty() || !word.is_ascii() {
+                continue;
+            }
+            if buckets.len() <= word.len() {
+                buckets.resize(word.len() + 1, vec![]);
+            }
+            buckets[word.len()].push(word);
+        }
+
+        let by_length = buckets
+            .into_iter()
+            .map(|mut bucket| {
+                bucket.sort();
+                bucket.dedup();
+                bucket.concat()
+            })
+            .collect();
+
+        Self { by_length }
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        let len = word.len();
+        let bucket = match self.by_length.get(len) {
+            Some(bucket) if len > 0 => bucket,
+            _ => return false,
+        };
+
+        let (mut lo, mut hi) = (0, bucket.len() / len);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            match bucket[mid * len..(mid + 1) * len].cmp(word) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return true,
+            }
+        }
+
+        false
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_length
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(len, bucket)| bucket.len() / len)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// frequency tiers of the words
+#[derive(Debug)]
+pub struct WordRanks {
+    top_10k: WordSet,
+    top_20k: WordSet,
+}
+
+impl WordRanks {
+    pub fn new() -> Result<Self, std::io::Error> {
+        let top_10k = std::fs::read_to_string("dictionaries/english/most_frequent_1-10k")?;
+        let top_20k = std::fs::read_to_string("dictionaries/english/most_frequent_10k-20k")?;
+
+        Ok(Self::from_lists(&top_10k, &top_20k))
+    }
+
+    pub fn from_lists(top_10k: &str, top_20k: &str) -> Self {
+        Self {
+            top_10k: WordSet::from_words(top_10k.lines()),
+            top_20k: WordSet::from_words(top_20k.lines()),
+        }
+    }
+
+    // 0 in the 10k most frequent words, 1 in the next 10k, 2 past them
+    pub fn tier(&self, word: &str) -> usize {
+        if self.top_10k.contains(word) {
+            0
+        } else if self.top_20k.contains(word) {
+            1
+        } else {
+            2
+        }
+    }
+}
+
+// the browser build can't read the dictionaries folder, see index.html
+pub async fn fetch_word_set(_c: ()) -> Result<WordSet, String> {
+    let text = reqwasm::http::Request::get("/most_frequent_words")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(WordSet::from_words(text.lines()))
+}
+
 fn _write_dictionary(path: &str, words: &Vec<String>) -> Result<(), ()> {
     let mut file = File::create(path).map_err(|_e| ())?;
     for word in words {
@@ -283,7 +455,7 @@ fn _write_dictionary(path: &str, words: &Vec<String>) -> Result<(), ()> {
 }
 #[cfg(test)]
 mod test {
-    use super::Dictionary;
+    use super::{Dictionary, WordSet};
     use rand::{seq::SliceRandom, thread_rng};
     use std::result::Result;
 
@@ -337,4 +509,23 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn word_set() {
+        let words = std::fs::read_to_string("dictionaries/english/most_frequent_words").unwrap();
+        let set = WordSet::from_words(words.lines());
+
+        assert!(set.contains("the"));
+        assert!(set.contains("house"));
+        assert!(!set.contains("hhouse"));
+        assert!(!set.contains(""));
+        assert_eq!(
+            set.len(),
+            words
+                .lines()
+                .filter(|w| !w.is_empty())
+                .collect::<std::collections::HashSet<_>>()
+                .len()
+        );
+    }
 }
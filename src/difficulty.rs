@@ -0,0 +1,181 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::beehive_swap::BeehiveSwap;
+use crate::board::Board;
+use crate::dictionary::{Dictionary, WordRanks};
+use crate::solver::{Budget, Outcome};
+
+// fills counted at most, more than that is just as ambiguous
+const MAX_FILLS: usize = 10;
+// scores below them are easy, then medium. picked so that each level gets a share
+// of the stock corpus, its scores sit between 0.42 and 0.61
+const EASY_BELOW: f64 = 0.5;
+const MEDIUM_BELOW: f64 = 0.58;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+    fn from_score(score: f64) -> Self {
+        if score < EASY_BELOW {
+            Difficulty::Easy
+        } else if score < MEDIUM_BELOW {
+            Difficulty::Medium
+        } else {
+            Difficulty::Hard
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "easy"),
+            Difficulty::Medium => write!(f, "medium"),
+            Difficulty::Hard => write!(f, "hard"),
+        }
+    }
+}
+
+impl std::str::FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Difficulty::ALL
+            .into_iter()
+            .find(|d| d.to_string() == s)
+            .ok_or(format!(
+                "unknown difficulty {}, expected easy, medium or hard",
+                s
+            ))
+    }
+}
+
+// what a puzzle's difficulty is made of, stored next to it in the corpus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rating {
+    pub difficulty: Difficulty,
+    // 0 for the easiest puzzle, 1 for the hardest
+    pub score: f64,
+    pub slots: usize,
+    // outside the 10k most frequent words
+    pub uncommon_words: usize,
+    // outside the 20k most frequent words
+    pub rare_words: usize,
+    pub swaps: usize,
+    // swappable letters that start green or yellow
+    pub hints: usize,
+    // valid fills of the letters, up to MAX_FILLS, none when counting ran out of budget
+    pub fills: Option<usize>,
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({:.2}): {} slots, {} uncommon and {} rare words, {} swaps, {} hints, ",
+            self.difficulty,
+            self.score,
+            self.slots,
+            self.uncommon_words,
+            self.rare_words,
+            self.swaps,
+            self.hints
+        )?;
+        match self.fills {
+            Some(fills) if fills >= MAX_FILLS => write!(f, "{}+ fills", MAX_FILLS),
+            Some(fills) => write!(f, "{} fills", fills),
+            None => write!(f, "fills not counted"),
+        }
+    }
+}
+
+// every factor is brought between 0 (easy) and 1 (hard) before being weighted
+pub fn rate(
+    swap: &BeehiveSwap,
+    ranks: &WordRanks,
+    dictionary: &Dictionary,
+    budget: Budget,
+) -> Rating {
+    let words = swap.solved_grid().slot_words();
+    let slots = words.len();
+    let tiers: Vec<usize> = words.iter().map(|w| ranks.tier(w)).collect();
+    let uncommon_words = tiers.iter().filter(|t| **t == 1).count();
+    let rare_words = tiers.iter().filter(|t| **t == 2).count();
+
+    let swappable = swap.get_swappable_cells().len().max(1);
+    let swaps = swap.min_swaps();
+    let hints = swap.hint_count();
+    let fills = match swap.count_solutions(dictionary, MAX_FILLS, budget).0 {
+        Outcome::Solved(fills) => Some(fills),
+        _ => None,
+    };
+
+    let vocabulary = tiers.iter().sum::<usize>() as f64 / (2 * slots.max(1)) as f64;
+    let size = (slots as f64 / 20.0).min(1.0);
+    let scramble = (swaps as f64 / swappable as f64).min(1.0);
+    let hidden = 1.0 - (hints as f64 / swappable as f64).min(1.0);
+    // any valid fill wins, the more there are the easier it gets
+    let strictness = 1.0 / fills.unwrap_or(MAX_FILLS).max(1) as f64;
+
+    let score = 0.3 * vocabulary + 0.15 * size + 0.2 * scramble + 0.2 * hidden + 0.15 * strictness;
+
+    Rating {
+        difficulty: Difficulty::from_score(score),
+        score,
+        slots,
+        uncommon_words,
+        rare_words,
+        swaps,
+        hints,
+        fills,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{rate, Difficulty};
+    use crate::beehive_swap::BeehiveSwap;
+    use crate::board::Board;
+    use crate::dictionary::{Dictionary, WordRanks};
+    use crate::grid_beehive::GridBeehive;
+    use crate::solver::Budget;
+
+    fn grid(rows: [&str; 6]) -> GridBeehive {
+        let mut grid = GridBeehive::new(6, 6);
+        for (r, row) in rows.iter().enumerate() {
+            grid.set_row(r, row.to_string());
+        }
+
+        grid
+    }
+
+    #[test]
+    fn rate_sample() {
+        let words = std::fs::read_to_string("dictionaries/english/most_frequent_words").unwrap();
+        let dictionary = Dictionary::from_words(words.lines().map(|w| w.to_string()).collect());
+        let ranks = WordRanks::new().unwrap();
+
+        let solved = grid(["__yeah", "_h__so", "sofa_t", "t_r_i_", "a_ex__", "the___"]);
+        let scrambled = grid(["__yhae", "_s__ho", "sofa_t", "t_r_e_", "i_ax__", "the___"]);
+        let locked = crate::beehive_swap::layout_locked_cells(&solved);
+        assert_eq!(locked.len(), 6);
+        let swap = BeehiveSwap::from_layouts(solved, scrambled, locked).unwrap();
+        assert_eq!(swap.min_swaps(), 4);
+
+        let rating = rate(&swap, &ranks, &dictionary, Budget::default());
+        println!("{}", rating);
+        assert!(rating.fills.unwrap() >= 1);
+        assert!((0.0..=1.0).contains(&rating.score));
+        assert_eq!("hard".parse::<Difficulty>(), Ok(Difficulty::Hard));
+    }
+}
@@ -1,11 +1,9 @@
-use rand::{seq::SliceRandom, thread_rng, Rng};
 use simple_matrix::Matrix;
-use std::{
-    collections::{HashSet, VecDeque},
-    fmt,
-};
+use std::fmt;
 
+use crate::board::{Axes, Board, Line};
 use crate::dictionary::Dictionary;
+use crate::solver::{Budget, Outcome, SearchOptions, SearchStats};
 
 #[derive(Clone, Debug)]
 pub struct Grid {
@@ -13,24 +11,6 @@ pub struct Grid {
     resolved_lines: Vec<Line>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
-pub enum Kind {
-    Row,
-    Col,
-}
-
-#[derive(Clone, Debug, PartialEq)]
-pub struct Line {
-    index: usize,
-    kind: Kind,
-}
-
-#[derive(Clone, PartialEq, Eq, Hash)]
-pub struct Cell {
-    row: usize,
-    col: usize,
-}
-
 impl Grid {
     pub fn new(rows: usize, cols: usize) -> Self {
         Self {
@@ -39,293 +19,51 @@ impl Grid {
         }
     }
 
-    pub fn rows(&self) -> usize {
-        self.layout.rows()
-    }
-    pub fn cols(&self) -> usize {
-        self.layout.cols()
+    // blacks are added where the slots can't be filled as they are
+    pub fn generate(&self, dictionary: &Dictionary) -> Option<Self> {
+        self.generate_within(dictionary, Budget::default(), &SearchOptions::default())
+            .0
+            .solved()
     }
 
-    // getters
-    pub fn get_row(&self, row: usize) -> Option<String> {
-        self.layout.get_row(row).map(|iter| {
-            let str: String = iter.collect();
-            str
-        })
-    }
-    pub fn get_col(&self, col: usize) -> Option<String> {
-        self.layout.get_col(col).map(|iter| {
-            let str: String = iter.collect();
-            str
-        })
-    }
-    pub fn get_cell(&self, row: usize, col: usize) -> Option<&char> {
-        self.layout.get(row, col)
-    }
-    pub fn get_line(&self, line: &Line) -> Option<String> {
-        match line.kind {
-            Kind::Row => self.get_row(line.index),
-            Kind::Col => self.get_col(line.index),
-        }
+    pub fn generate_within(
+        &self,
+        dictionary: &Dictionary,
+        budget: Budget,
+        options: &SearchOptions,
+    ) -> (Outcome<Self>, SearchStats) {
+        Board::generate_within(self, dictionary, true, budget, options)
     }
 
-    // setters
-    pub fn set_cell(&mut self, row: usize, col: usize, val: char) {
-        self.layout.set(row, col, val);
-    }
-    pub fn set_line(&mut self, line: &Line, val: String) {
-        let i = line.index;
-        match line.kind {
-            Kind::Row => self.set_row(i, val),
-            Kind::Col => self.set_col(i, val),
-        }
-    }
-    pub fn set_row(&mut self, row: usize, val: String) {
-        for (col, char) in val.chars().enumerate() {
-            self.set_cell(row, col, char);
-        }
-    }
-    pub fn set_col(&mut self, col: usize, val: String) {
-        for (row, char) in val.chars().enumerate() {
-            self.set_cell(row, col, char);
-        }
-    }
-
-    pub fn flag_resolved(&mut self, line: &Line) {
-        self.resolved_lines.push(line.clone());
-    }
-    pub fn next_line(&self) -> Option<Line> {
-        let rows: Vec<Line> = (0..self.rows())
-            .map(|i| Line {
-                index: i,
-                kind: Kind::Row,
-            })
-            .collect();
-        let cols: Vec<Line> = (0..self.cols())
-            .map(|i| Line {
-                index: i,
-                kind: Kind::Col,
-            })
-            .collect();
-        let lines = [rows.as_slice(), cols.as_slice()].concat();
-
-        let mut unresolved_lines: Vec<Line> = lines
-            .into_iter()
-            .filter(|l| !self.resolved_lines.contains(l))
-            .collect();
-
-        unresolved_lines.sort_by(|line_a, line_b| {
-            let val_a = self.get_line(line_a).unwrap().replace('\0', ".");
-            let val_b = self.get_line(line_b).unwrap().replace('\0', ".");
-
-            // count the number of constrained cells, cells with already a letter or a '_' and not a '.'
-            let liberties_a = &val_a.chars().filter(|c| c == &'.').count();
-            let liberties_b = &val_b.chars().filter(|c| c == &'.').count();
-
-            let blacks_a = &val_a.chars().filter(|c| c == &'_').count();
-            let blacks_b = &val_b.chars().filter(|c| c == &'_').count();
-
-            let constrains_a = &val_a.len() - liberties_a - blacks_a;
-            let constrains_b = &val_b.len() - liberties_b - blacks_b;
-
-            // println!("line_a {:?}-{}, val '{}', {} liberties, {} blacks, {} constrains", &line_a.kind, &line_a.index, &val_a.replace('\0', "."), liberties_a, blacks_a, constrains_a);
-            // println!("line_a {:?}-{}, val '{}', {} liberties, {} blacks, {} constrains", &line_b.kind, &line_b.index, &val_b.replace('\0', "."), liberties_b, blacks_b, constrains_b);
-
-            // most constrained first
-            match constrains_b.cmp(&constrains_a) {
-                std::cmp::Ordering::Equal => liberties_b.cmp(liberties_a),
-                res => res,
-            }
-        });
-
-        unresolved_lines.into_iter().next()
-    }
-
-    // incr
-    pub fn pattern(&self, line: &Line) -> Option<String> {
-        self.get_line(line).map(|s| s.replace('\0', "."))
-    }
-
-    pub fn is_invalid(&self, dictionary: &Dictionary) -> bool {
-        self.has_duplicates() || self.has_forbidden_tupples(dictionary) || self.has_isles()
-    }
-    pub fn has_duplicates(&self) -> bool {
-        let mut words_set: HashSet<String> = HashSet::new();
-
-        for i in 0..self.layout.rows() {
-            let words = self.get_row(i).unwrap_or(String::from("\0"));
-            let words: Vec<String> = words.split('_').map(|w| String::from(w)).collect();
-            let words: Vec<String> = words
-                .into_iter()
-                .filter(|w| w.len() > 1)
-                .filter(|w| !w.contains('\0'))
-                .collect();
-
-            for word in words {
-                if words_set.contains(&word) {
-                    return true;
-                }
-                words_set.insert(word);
+    // words across the even rows and down the even cols, holes where the odd ones
+    // cross, like a waffle
+    pub fn new_waffle(size: usize) -> Self {
+        let mut grid = Grid::new(size, size);
+        for r in (1..size).step_by(2) {
+            for c in (1..size).step_by(2) {
+                grid.set_cell(r, c, '_');
             }
         }
 
-        for j in 0..self.layout.cols() {
-            let words = self.get_col(j).unwrap_or(String::from("\0"));
-            let words: Vec<String> = words.split('_').map(|w| String::from(w)).collect();
-            let words: Vec<String> = words
-                .into_iter()
-                .filter(|w| w.len() > 1)
-                .filter(|w| !w.contains('\0'))
-                .collect();
-
-            for word in words {
-                if words_set.contains(&word) {
-                    return true;
-                }
-                words_set.insert(word);
-            }
-        }
-
-        false
+        grid
     }
-    pub fn has_forbidden_tupples(&self, dictionary: &Dictionary) -> bool {
-        for r in 0..self.layout.rows() {
-            let row = self.get_row(r).unwrap_or("".to_string());
-            if dictionary.has_forbidden_tuples(row).unwrap_or(false) {
-                return true;
-            }
-        }
-        for c in 0..self.layout.cols() {
-            let col = self.get_col(c).unwrap_or("".to_string());
-            if dictionary.has_forbidden_tuples(col).unwrap_or(false) {
-                return true;
-            }
-        }
+}
 
-        false
+impl Board for Grid {
+    fn matrix(&self) -> &Matrix<char> {
+        &self.layout
     }
-    pub fn has_isles(&self) -> bool {
-        let mut visited_cells: HashSet<Cell> = HashSet::new();
-        // visit all blacks to enforce boundaries
-        for r in 0..self.rows() {
-            for c in 0..self.cols() {
-                if self.get_cell(r, c).is_some() && self.get_cell(r, c).unwrap() == &'_' {
-                    visited_cells.insert(Cell { row: r, col: c });
-                }
-            }
-        }
-
-        // find an anchor
-        let mut rng = rand::thread_rng();
-        let mut anchor = Cell { row: 0, col: 0 };
-        while self.get_cell(anchor.row, anchor.col).unwrap_or(&'_') == &'_' {
-            anchor = Cell {
-                row: rng.gen_range(0..self.rows()),
-                col: rng.gen_range(0..self.cols()),
-            };
-        }
-
-        let mut visit_queue = VecDeque::new();
-        visit_queue.push_front(anchor);
-
-        while let Some(cell) = visit_queue.pop_back() {
-            // visit cell
-            visited_cells.insert(cell.clone());
-
-            // find neighbours
-            let mut neighbours = vec![];
-            if cell.row > 0 {
-                let top = Cell {
-                    row: cell.row - 1,
-                    col: cell.col,
-                };
-                neighbours.push(top);
-            }
-            if cell.col > 0 {
-                let left = Cell {
-                    row: cell.row,
-                    col: cell.col - 1,
-                };
-                neighbours.push(left);
-            }
-            let bottom = Cell {
-                row: cell.row + 1,
-                col: cell.col,
-            };
-            neighbours.push(bottom);
-            let right = Cell {
-                row: cell.row,
-                col: cell.col + 1,
-            };
-            neighbours.push(right);
-
-            // add unvisited neighbours to the visit_queue
-            neighbours
-                .into_iter()
-                .filter(|n| self.get_cell(n.row, n.col).is_some())
-                .filter(|n| !visited_cells.contains(&n))
-                .for_each(|n| visit_queue.push_front(n));
-        }
-
-        visited_cells.len() < self.rows() * self.cols()
+    fn matrix_mut(&mut self) -> &mut Matrix<char> {
+        &mut self.layout
     }
-
-    fn _get_depth(&self) -> usize {
-        for c in 0..self.layout.cols() {
-            for o in 0..1 {
-                if self.get_cell(c + o, c).unwrap_or(&'\0') == &'\0' {
-                    return 2 * c + o;
-                }
-            }
-        }
-        0
+    fn resolved_lines(&self) -> &Vec<Line> {
+        &self.resolved_lines
     }
-
-    pub fn recursive_generate(&self, dictionary: &Dictionary) -> Option<Self> {
-        let opt_next_line = self.next_line();
-        // grid is complete
-        if opt_next_line.is_none() {
-            return Some(self.clone());
-        }
-
-        // grid invalid
-        if self.is_invalid(dictionary) {
-            return None;
-        }
-
-        let next_line = opt_next_line.unwrap();
-
-        let next_pattern = self.pattern(&next_line);
-
-        if let Some(pattern) = next_pattern {
-            // strict layout
-            // let common_candidates = &dictionary.recursive_find_candidates(pattern.clone()).unwrap_or(vec![]);
-            // allow the algo to add blanks
-            let common_candidates = &dictionary
-                .find_candidates_allow_split(pattern.clone())
-                .unwrap_or(vec![]);
-            let mut common_candidates = common_candidates.clone();
-            common_candidates.shuffle(&mut thread_rng());
-
-            let candidates = common_candidates;
-            let mut candidates = candidates.into_iter();
-
-            while let Some(candidate) = candidates.next() {
-                let mut incr_grid = self.clone();
-                incr_grid.set_line(&next_line, candidate);
-                incr_grid.flag_resolved(&next_line);
-
-                if let Some(complete_grid) = incr_grid.recursive_generate(dictionary) {
-                    return Some(complete_grid);
-                }
-            }
-        } else {
-            // grid full
-            return Some(self.clone());
-        }
-
-        // no grid found
-        None
+    fn resolved_lines_mut(&mut self) -> &mut Vec<Line> {
+        &mut self.resolved_lines
+    }
+    fn axes(&self) -> Axes {
+        Axes::SQUARE
     }
 }
 
@@ -360,10 +98,38 @@ mod test {
 
     use std::time::Instant;
 
+    use crate::board::Board;
     use crate::dictionary;
+    use crate::solver::{Budget, Outcome, SearchOptions};
 
     use super::Grid;
 
+    #[test]
+    fn generate_within_budget() {
+        let words = std::fs::read_to_string("dictionaries/english/most_frequent_words").unwrap();
+        let dictionary =
+            dictionary::Dictionary::from_words(words.lines().map(|w| w.to_string()).collect());
+        let empty = Grid::new(4, 4);
+
+        let budget = Budget {
+            nodes: Some(3),
+            ..Default::default()
+        };
+        let (outcome, stats) = empty.generate_within(&dictionary, budget, &Default::default());
+        assert!(matches!(outcome, Outcome::Timeout));
+        assert_eq!(stats.nodes, 3);
+
+        let options = SearchOptions {
+            trace: true,
+            ..Default::default()
+        };
+        let (outcome, stats) = empty.generate_within(&dictionary, Budget::default(), &options);
+        println!("{}", stats);
+        assert!(outcome.solved().is_some());
+        // 4 rows and 4 cols
+        assert_eq!(stats.trace.unwrap().fill_order.len(), 8);
+    }
+
     #[test]
     fn gen_grid() {
         let start = Instant::now();
@@ -372,7 +138,7 @@ mod test {
         println!("dictionary created in {:?}", elapsed);
 
         let grid = Grid::new(3, 2);
-        let full = grid.recursive_generate(&dictionary);
+        let full = grid.generate(&dictionary);
         let elapsed = start.elapsed();
         println!("grid created in {:?}", elapsed);
         println!("{:?}", full);
@@ -0,0 +1,129 @@
+use std::ops::{Add, Mul, Sub};
+
+// axial coordinates on the hex lattice, q runs along a row and r down a col,
+// the third cube coordinate s = -q - r runs along the diags
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+// counterclockwise from the right neighbour
+pub const DIRECTIONS: [Hex; 6] = [
+    Hex::new(1, 0),
+    Hex::new(1, -1),
+    Hex::new(0, -1),
+    Hex::new(-1, 0),
+    Hex::new(-1, 1),
+    Hex::new(0, 1),
+];
+
+impl Hex {
+    pub const fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+    pub fn s(&self) -> i32 {
+        -self.q - self.r
+    }
+
+    // a matrix cell, the rhombus boards are stored in
+    pub fn from_cell(row: usize, col: usize) -> Self {
+        Self::new(col as i32, row as i32)
+    }
+    pub fn to_cell(self) -> Option<(usize, usize)> {
+        if self.q < 0 || self.r < 0 {
+            return None;
+        }
+
+        Some((self.r as usize, self.q as usize))
+    }
+
+    pub fn neighbours(self) -> [Hex; 6] {
+        DIRECTIONS.map(|d| self + d)
+    }
+    pub fn distance(self, other: Hex) -> i32 {
+        let d = self - other;
+        (d.q.abs() + d.r.abs() + d.s().abs()) / 2
+    }
+
+    // a sixth of a turn clockwise around `center`, (q, r, s) -> (-r, -s, -q)
+    pub fn rotate_around(self, center: Hex) -> Hex {
+        let d = self - center;
+        center + Hex::new(-d.r, -d.s())
+    }
+
+    // the hexes at exactly `radius`, starting bottom left and going counterclockwise
+    pub fn ring(self, radius: usize) -> Vec<Hex> {
+        if radius == 0 {
+            return vec![self];
+        }
+
+        let mut hex = self + DIRECTIONS[4] * radius as i32;
+        let mut ring = vec![];
+        for direction in DIRECTIONS {
+            for _ in 0..radius {
+                ring.push(hex);
+                hex = hex + direction;
+            }
+        }
+
+        ring
+    }
+    // the centre first, then each ring outwards
+    pub fn spiral(self, radius: usize) -> Vec<Hex> {
+        (0..=radius).flat_map(|n| self.ring(n)).collect()
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, other: Hex) -> Hex {
+        Hex::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, other: Hex) -> Hex {
+        Hex::new(self.q - other.q, self.r - other.r)
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Hex;
+
+    fn mul(self, k: i32) -> Hex {
+        Hex::new(self.q * k, self.r * k)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Hex, DIRECTIONS};
+
+    #[test]
+    fn hex_lattice() {
+        let center = Hex::new(3, 3);
+        assert!(center.neighbours().iter().all(|n| n.distance(center) == 1));
+        assert_eq!(Hex::new(0, 0).distance(Hex::new(2, -3)), 3);
+        assert_eq!(Hex::new(1, 2).to_cell(), Some((2, 1)));
+        assert_eq!(Hex::new(-1, 2).to_cell(), None);
+
+        for radius in 0..4 {
+            let ring = center.ring(radius);
+            assert_eq!(ring.len(), (6 * radius).max(1));
+            assert!(ring.iter().all(|h| h.distance(center) == radius as i32));
+        }
+        assert_eq!(center.spiral(2).len(), 19);
+
+        let mut hex = Hex::new(5, 1);
+        for _ in 0..6 {
+            hex = hex.rotate_around(center);
+            assert_eq!(hex.distance(center), 2);
+        }
+        assert_eq!(hex, Hex::new(5, 1));
+        assert_eq!(DIRECTIONS[0].rotate_around(Hex::default()), DIRECTIONS[5]);
+    }
+}
@@ -0,0 +1,347 @@
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use crate::board::{Board, Kind};
+use crate::grid::Grid;
+use crate::grid_beehive::GridBeehive;
+
+const CROSSWORD_KIND: &str = "http://ipuz.org/crossword#1";
+// extension for hex boards: rows are shifted right by half a cell each, which makes
+// a third line direction read from bottom left to top right
+const HEX_KIND: &str = "https://github.com/lucienbertin/beehive#hex";
+const BLOCK: &str = "#";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IpuzError {
+    Json(String),
+    MissingField(&'static str),
+    Dimensions,
+}
+
+impl fmt::Display for IpuzError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpuzError::Json(e) => write!(f, "not a valid ipuz file: {}", e),
+            IpuzError::MissingField(field) => write!(f, "ipuz file has no '{}'", field),
+            IpuzError::Dimensions => write!(f, "ipuz grid doesn't match its dimensions"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum IpuzGrid {
+    Square(Grid),
+    Hex(GridBeehive),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IpuzClue {
+    pub direction: String,
+    pub number: usize,
+    pub clue: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Ipuz {
+    pub grid: IpuzGrid,
+    pub clues: Vec<IpuzClue>,
+}
+
+// a word slot reduced to what ipuz cares about
+struct IpuzSlot {
+    direction: &'static str,
+    start: (usize, usize),
+}
+
+pub fn grid_to_ipuz(grid: &Grid) -> Value {
+    board_to_ipuz(grid, false)
+}
+
+pub fn beehive_to_ipuz(grid: &GridBeehive) -> Value {
+    board_to_ipuz(grid, true)
+}
+
+fn board_to_ipuz<B: Board>(grid: &B, hex: bool) -> Value {
+    let slots = grid
+        .slots()
+        .into_iter()
+        .map(|slot| IpuzSlot {
+            direction: direction_name(slot.line.kind()),
+            start: (slot.cells[0].row(), slot.cells[0].col()),
+        })
+        .collect();
+
+    to_ipuz(
+        grid.rows(),
+        grid.cols(),
+        |r, c| *grid.get_cell(r, c).unwrap_or(&'_'),
+        slots,
+        hex,
+    )
+}
+
+// the clue list heading of a direction
+pub fn direction_name(kind: &Kind) -> &'static str {
+    match kind {
+        Kind::Row => "Across",
+        Kind::Col => "Down",
+        Kind::Diag => "Diagonal Up",
+    }
+}
+
+// the same name lowercased, the way crossword files spell it
+pub fn direction_key(kind: &Kind) -> String {
+    direction_name(kind).to_lowercase()
+}
+
+fn to_ipuz(
+    rows: usize,
+    cols: usize,
+    get_cell: impl Fn(usize, usize) -> char,
+    slots: Vec<IpuzSlot>,
+    hex: bool,
+) -> Value {
+    // standard numbering: row by row, one number per cell starting a slot
+    let mut starts: Vec<(usize, usize)> = slots.iter().map(|s| s.start).collect();
+    starts.sort();
+    starts.dedup();
+    let number = |start: (usize, usize)| starts.iter().position(|s| s == &start).unwrap() + 1;
+
+    let mut puzzle = vec![];
+    let mut solution = vec![];
+    for r in 0..rows {
+        let mut puzzle_row = vec![];
+        let mut solution_row = vec![];
+        for c in 0..cols {
+            match get_cell(r, c) {
+                '_' => {
+                    puzzle_row.push(json!(BLOCK));
+                    solution_row.push(json!(BLOCK));
+                }
+                letter => {
+                    match starts.contains(&(r, c)) {
+                        true => puzzle_row.push(json!(number((r, c)))),
+                        false => puzzle_row.push(json!(0)),
+                    }
+                    match letter {
+                        '\0' => solution_row.push(Value::Null),
+                        _ => solution_row.push(json!(letter.to_uppercase().to_string())),
+                    }
+                }
+            }
+        }
+        puzzle.push(puzzle_row);
+        solution.push(solution_row);
+    }
+
+    let mut clues = serde_json::Map::new();
+    for slot in &slots {
+        let entry = clues
+            .entry(slot.direction.to_string())
+            .or_insert_with(|| json!([]));
+        entry
+            .as_array_mut()
+            .unwrap()
+            .push(json!([number(slot.start), ""]));
+    }
+    for list in clues.values_mut() {
+        list.as_array_mut()
+            .unwrap()
+            .sort_by_key(|clue| clue[0].as_u64());
+    }
+
+    let mut kind = vec![CROSSWORD_KIND];
+    if hex {
+        kind.push(HEX_KIND);
+    }
+
+    json!({
+        "version": "http://ipuz.org/v2",
+        "kind": kind,
+        "dimensions": { "width": cols, "height": rows },
+        "block": BLOCK,
+        "empty": 0,
+        "puzzle": puzzle,
+        "solution": solution,
+        "clues": clues,
+    })
+}
+
+pub fn from_ipuz(contents: &str) -> Result<Ipuz, IpuzError> {
+    let value: Value =
+        serde_json::from_str(contents).map_err(|e| IpuzError::Json(e.to_string()))?;
+
+    let dimensions = value
+        .get("dimensions")
+        .ok_or(IpuzError::MissingField("dimensions"))?;
+    let cols = dimensions["width"]
+        .as_u64()
+        .ok_or(IpuzError::MissingField("width"))? as usize;
+    let rows = dimensions["height"]
+        .as_u64()
+        .ok_or(IpuzError::MissingField("height"))? as usize;
+    let block = value["block"].as_str().unwrap_or(BLOCK);
+    let hex = value["kind"]
+        .as_array()
+        .map(|kinds| kinds.iter().any(|k| k.as_str() == Some(HEX_KIND)))
+        .unwrap_or(false);
+
+    let puzzle = value["puzzle"]
+        .as_array()
+        .ok_or(IpuzError::MissingField("puzzle"))?;
+    if puzzle.len() != rows {
+        return Err(IpuzError::Dimensions);
+    }
+
+    let mut layout = vec![];
+    for r in 0..rows {
+        let row = puzzle[r].as_array().ok_or(IpuzError::Dimensions)?;
+        if row.len() != cols {
+            return Err(IpuzError::Dimensions);
+        }
+
+        let mut line = String::new();
+        for (c, cell) in row.iter().enumerate() {
+            // omitted (null) cells have no place in our grids, they become blocks
+            let is_block = cell.is_null() || cell.as_str() == Some(block);
+            let letter = value["solution"][r][c]
+                .as_str()
+                .and_then(|s| s.chars().next())
+                .filter(|l| l.is_alphabetic())
+                .map(|l| l.to_ascii_lowercase());
+            match (is_block, letter) {
+                (true, _) => line.push('_'),
+                (false, Some(l)) => line.push(l),
+                (false, None) => line.push('\0'),
+            }
+        }
+        layout.push(line);
+    }
+
+    let grid = if hex {
+        let mut grid = GridBeehive::new(rows, cols);
+        for (r, line) in layout.into_iter().enumerate() {
+            grid.set_row(r, line);
+        }
+        IpuzGrid::Hex(grid)
+    } else {
+        let mut grid = Grid::new(rows, cols);
+        for (r, line) in layout.into_iter().enumerate() {
+            grid.set_row(r, line);
+        }
+        IpuzGrid::Square(grid)
+    };
+
+    let mut clues = vec![];
+    if let Some(directions) = value["clues"].as_object() {
+        for (direction, list) in directions {
+            for clue in list.as_array().into_iter().flatten() {
+                // either [number, "clue"] or { "number": n, "clue": "..." }
+                let (number, text) = match clue {
+                    Value::Array(pair) => (pair.first(), pair.get(1)),
+                    Value::Object(map) => (map.get("number"), map.get("clue")),
+                    _ => (None, None),
+                };
+                if let Some(number) = number.and_then(|n| n.as_u64()) {
+                    clues.push(IpuzClue {
+                        direction: direction.clone(),
+                        number: number as usize,
+                        clue: text.and_then(|t| t.as_str()).unwrap_or("").to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(Ipuz { grid, clues })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{beehive_to_ipuz, from_ipuz, grid_to_ipuz, IpuzGrid};
+    use crate::board::{Board, Kind};
+    use crate::grid::Grid;
+    use crate::grid_beehive::GridBeehive;
+
+    #[test]
+    fn square_round_trip() {
+        let mut grid = Grid::new(3, 3);
+        grid.set_row(0, "cat".to_string());
+        grid.set_row(1, "a_o".to_string());
+        grid.set_row(2, "b\0y".to_string());
+
+        let ipuz = grid_to_ipuz(&grid);
+        assert_eq!(ipuz["puzzle"][0][0], 1);
+        assert_eq!(ipuz["puzzle"][0][2], 2);
+        assert_eq!(ipuz["puzzle"][2][0], 3);
+        assert_eq!(ipuz["puzzle"][1][1], "#");
+        assert_eq!(ipuz["solution"][2][1], serde_json::Value::Null);
+        assert_eq!(ipuz["clues"]["Across"].as_array().unwrap().len(), 2);
+        assert_eq!(ipuz["clues"]["Down"].as_array().unwrap().len(), 2);
+
+        let imported = from_ipuz(&ipuz.to_string()).unwrap();
+        match imported.grid {
+            IpuzGrid::Square(g) => {
+                assert_eq!(g.get_row(0), Some("cat".to_string()));
+                assert_eq!(g.get_row(1), Some("a_o".to_string()));
+                assert_eq!(g.get_row(2), Some("b\0y".to_string()));
+            }
+            IpuzGrid::Hex(_) => panic!("square grid imported as hex"),
+        }
+        assert_eq!(imported.clues.len(), 4);
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        let mut grid = GridBeehive::new(6, 6);
+        grid.set_row(0, "__yeah".to_string());
+        grid.set_row(1, "_h__so".to_string());
+        grid.set_row(2, "sofa_t".to_string());
+        grid.set_row(3, "t_r_i_".to_string());
+        grid.set_row(4, "a_ex__".to_string());
+        grid.set_row(5, "the___".to_string());
+
+        let ipuz = beehive_to_ipuz(&grid);
+        let diagonals = ipuz["clues"]["Diagonal Up"].as_array().unwrap();
+        assert_eq!(
+            diagonals.len(),
+            grid.slots()
+                .iter()
+                .filter(|s| s.line.kind() == &Kind::Diag)
+                .count()
+        );
+
+        match from_ipuz(&ipuz.to_string()).unwrap().grid {
+            IpuzGrid::Hex(g) => {
+                for r in 0..6 {
+                    assert_eq!(g.get_row(r), grid.get_row(r));
+                }
+            }
+            IpuzGrid::Square(_) => panic!("hex grid imported as square"),
+        }
+    }
+
+    #[test]
+    fn import_external() {
+        // omitted cells and object clues, as other tools write them
+        let ipuz = from_ipuz(
+            r##"{
+                "version": "http://ipuz.org/v2",
+                "kind": ["http://ipuz.org/crossword#1"],
+                "dimensions": { "width": 2, "height": 2 },
+                "puzzle": [[1, 2], [null, 0]],
+                "solution": [["O", "N"], [null, "O"]],
+                "clues": { "Across": [{ "number": 1, "clue": "Switched" }], "Down": [[2, "Not yes"]] }
+            }"##,
+        )
+        .unwrap();
+
+        match ipuz.grid {
+            IpuzGrid::Square(g) => assert_eq!(g.get_row(1), Some("_o".to_string())),
+            IpuzGrid::Hex(_) => panic!("square grid imported as hex"),
+        }
+        assert_eq!(ipuz.clues.len(), 2);
+        assert!(from_ipuz("{}").is_err());
+    }
+}
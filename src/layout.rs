@@ -0,0 +1,323 @@
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::board::{Axes, Board, Cell};
+use crate::grid_beehive::GridBeehive;
+
+// shipped with the binary, `--layouts <file>` adds to them or overrides them
+const BUILTIN: &str = include_str!("../assets/layouts.txt");
+
+#[derive(Debug, Clone)]
+pub struct Layout {
+    pub name: String,
+    pub grid: GridBeehive,
+    // the generator may turn open cells into blocks
+    pub allow_adding_blacks: bool,
+    pub locked: Vec<Cell>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutError {
+    // 1-based, like editors show it
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Layout {
+    // the text a layout is read from, without its name
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        if self.allow_adding_blacks {
+            text.push_str("blacks = allowed\n");
+        }
+        if !self.grid.axes().is_all() {
+            text.push_str(&format!("lines = {}\n", self.grid.axes()));
+        }
+        if !self.locked.is_empty() {
+            let cells: Vec<String> = self
+                .locked
+                .iter()
+                .map(|c| format!("{},{}", c.row(), c.col()))
+                .collect();
+            text.push_str(&format!("locked = {}\n", cells.join(" ")));
+        }
+        for r in 0..self.grid.rows() {
+            let cells: Vec<String> = (0..self.grid.cols())
+                .map(|c| match *self.grid.get_cell(r, c).unwrap() {
+                    '\0' => ".".to_string(),
+                    '_' => "#".to_string(),
+                    l => l.to_string(),
+                })
+                .collect();
+            text.push_str(&format!("{}{}\n", " ".repeat(r), cells.join(" ")));
+        }
+
+        text
+    }
+}
+
+// reads every [name] section of a layouts file
+pub fn parse_layouts(text: &str) -> Result<Vec<Layout>, LayoutError> {
+    let mut layouts = vec![];
+    let mut current: Option<Section> = None;
+
+    for (i, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        let error = |message: String| LayoutError {
+            line: i + 1,
+            message,
+        };
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            if let Some(section) = current.take() {
+                layouts.push(build(section)?);
+            }
+            if name.trim().is_empty() {
+                return Err(error("a layout needs a name".to_string()));
+            }
+            current = Some(Section {
+                name: name.trim().to_string(),
+                allow_adding_blacks: false,
+                axes: Axes::ALL,
+                locked: vec![],
+                rows: vec![],
+                line: i + 1,
+            });
+            continue;
+        }
+
+        let section = current
+            .as_mut()
+            .ok_or(error("expected a [name] first".to_string()))?;
+        if let Some((key, value)) = line.split_once('=') {
+            match (key.trim(), value.trim()) {
+                ("blacks", "allowed") => section.allow_adding_blacks = true,
+                ("blacks", "no") => section.allow_adding_blacks = false,
+                ("lines", axes) => section.axes = axes.parse().map_err(error)?,
+                ("locked", cells) => {
+                    for cell in cells.split_whitespace() {
+                        let parsed = cell
+                            .split_once(',')
+                            .and_then(|(r, c)| Some(Cell::new(r.parse().ok()?, c.parse().ok()?)));
+                        section
+                            .locked
+                            .push(parsed.ok_or(error(format!("can't read cell {}", cell)))?);
+                    }
+                }
+                (key, value) => return Err(error(format!("unknown setting {} = {}", key, value))),
+            }
+            continue;
+        }
+
+        let mut row = vec![];
+        for c in line.chars().filter(|c| !c.is_whitespace()) {
+            row.push(match c {
+                '#' => '_',
+                '.' => '\0',
+                l if l.is_ascii_alphabetic() => l.to_ascii_lowercase(),
+                l => {
+                    return Err(error(format!(
+                        "unexpected {}, expected #, . or a letter",
+                        l
+                    )))
+                }
+            });
+        }
+        section.rows.push(row);
+    }
+    if let Some(section) = current.take() {
+        layouts.push(build(section)?);
+    }
+
+    Ok(layouts)
+}
+
+// a layout as read, before it's checked
+struct Section {
+    name: String,
+    allow_adding_blacks: bool,
+    axes: Axes,
+    locked: Vec<Cell>,
+    rows: Vec<Vec<char>>,
+    // where the section starts
+    line: usize,
+}
+
+fn build(section: Section) -> Result<Layout, LayoutError> {
+    let Section {
+        name,
+        allow_adding_blacks,
+        axes,
+        locked,
+        rows,
+        line,
+    } = section;
+    let error = |message: String| LayoutError { line, message };
+    let cols = rows.first().map(|r| r.len()).unwrap_or(0);
+    if cols == 0 {
+        return Err(error(format!("{} has no rows", name)));
+    }
+    if rows.iter().any(|r| r.len() != cols) {
+        return Err(error(format!(
+            "the rows of {} aren't all {} cells long",
+            name, cols
+        )));
+    }
+    if let Some(cell) = locked
+        .iter()
+        .find(|c| c.row() >= rows.len() || c.col() >= cols || rows[c.row()][c.col()] == '_')
+    {
+        return Err(error(format!(
+            "{} locks {},{} which isn't an open cell",
+            name,
+            cell.row(),
+            cell.col()
+        )));
+    }
+
+    let mut grid = GridBeehive::new(rows.len(), cols).with_axes(axes);
+    for (r, row) in rows.iter().enumerate() {
+        for (c, val) in row.iter().enumerate() {
+            grid.set_cell(r, c, *val);
+        }
+    }
+
+    Ok(Layout {
+        name,
+        grid,
+        allow_adding_blacks,
+        locked,
+    })
+}
+
+// named layouts, later ones replace earlier ones with the same name
+#[derive(Debug, Clone)]
+pub struct LayoutRegistry {
+    layouts: Vec<Layout>,
+}
+
+impl LayoutRegistry {
+    // parsed on first use, clone it to add layouts
+    pub fn builtin() -> &'static Self {
+        static BUILTIN_REGISTRY: OnceLock<LayoutRegistry> = OnceLock::new();
+        BUILTIN_REGISTRY.get_or_init(|| Self {
+            layouts: parse_layouts(BUILTIN).expect("assets/layouts.txt is broken"),
+        })
+    }
+
+    pub fn add(&mut self, layouts: Vec<Layout>) {
+        for layout in layouts {
+            self.layouts.retain(|l| l.name != layout.name);
+            self.layouts.push(layout);
+        }
+    }
+
+    pub fn load_file(&mut self, path: &str) -> Result<(), String> {
+        let text =
+            std::fs::read_to_string(path).map_err(|e| format!("can't read {}: {}", path, e))?;
+        let layouts = parse_layouts(&text).map_err(|e| format!("{}, {}", path, e))?;
+        self.add(layouts);
+
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Layout> {
+        self.layouts.iter().find(|l| l.name == name)
+    }
+
+    // the layout a board was made from: its blocks stay blocks, its letters stay
+    // put and only layouts that allow it gain new blocks
+    pub fn layout_of(&self, grid: &GridBeehive) -> Option<&Layout> {
+        self.layouts.iter().rev().find(|layout| {
+            let empty = &layout.grid;
+            empty.rows() == grid.rows()
+                && empty.cols() == grid.cols()
+                && empty.axes() == grid.axes()
+                && (0..grid.rows()).all(|r| {
+                    (0..grid.cols()).all(|c| {
+                        match (
+                            *empty.get_cell(r, c).unwrap(),
+                            *grid.get_cell(r, c).unwrap(),
+                        ) {
+                            ('_', filled) => filled == '_',
+                            ('\0', filled) => filled != '_' || layout.allow_adding_blacks,
+                            (letter, filled) => letter == filled,
+                        }
+                    })
+                })
+        })
+    }
+
+    pub fn layouts(&self) -> &Vec<Layout> {
+        &self.layouts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_layouts, LayoutRegistry};
+    use crate::board::Board;
+    use crate::grid_beehive::GridBeehive;
+
+    #[test]
+    fn builtin_layouts() {
+        let registry = LayoutRegistry::builtin();
+        let layout = registry.get("7x7-honeycomb").unwrap();
+        let mut expected = GridBeehive::new(7, 7);
+        expected.champfer_layout();
+        expected.set_row(1, "__\0_\0_\0".to_string());
+        expected.set_row(2, "_\0\0_\0\0\0".to_string());
+        expected.set_row(3, "\0_\0___\0".to_string());
+        expected.set_row(4, "\0\0_\0\0\0_".to_string());
+        expected.set_row(5, "\0_\0_\0__".to_string());
+        assert_eq!(format!("{}", layout.grid), format!("{}", expected));
+        assert!(!layout.allow_adding_blacks);
+
+        let mut filled = GridBeehive::new_343_honeycomb();
+        for r in 0..filled.rows() {
+            for c in 0..filled.cols() {
+                if *filled.get_cell(r, c).unwrap() == '\0' {
+                    filled.set_cell(r, c, 'a');
+                }
+            }
+        }
+        assert_eq!(registry.layout_of(&filled).unwrap().name, "343-honeycomb");
+        filled.set_cell(0, 2, '_');
+        assert!(registry.layout_of(&filled).is_none());
+        assert_eq!(registry.get("343-honeycomb").unwrap().locked.len(), 6);
+        assert!(registry.get("6x6-honeycomb").unwrap().allow_adding_blacks);
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "[small]\nblacks = allowed\nlines = row diag\nlocked = 1,1\n# . a\n . . .\n";
+        let layouts = parse_layouts(text).unwrap();
+        assert_eq!(layouts.len(), 1);
+        assert_eq!(layouts[0].grid.get_cell(0, 2), Some(&'a'));
+        assert_eq!(format!("[small]\n{}", layouts[0].to_text()), text);
+
+        let mut registry = LayoutRegistry::builtin().clone();
+        let cnt = registry.layouts().len();
+        registry.add(parse_layouts("[7x7-honeycomb]\n. .\n . .").unwrap());
+        assert_eq!(registry.layouts().len(), cnt);
+        assert_eq!(registry.get("7x7-honeycomb").unwrap().grid.rows(), 2);
+    }
+
+    #[test]
+    fn bad_layouts() {
+        assert_eq!(parse_layouts(". .").unwrap_err().line, 1);
+        assert_eq!(parse_layouts("[a]\n. .\n. . .").unwrap_err().line, 1);
+        assert_eq!(parse_layouts("[a]\n. ?").unwrap_err().line, 2);
+        assert!(parse_layouts("[a]\nlocked = 0,0\n# .").is_err());
+        assert_eq!(parse_layouts("[a]\nlines = row hex").unwrap_err().line, 2);
+    }
+}
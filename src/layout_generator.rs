@@ -0,0 +1,311 @@
+use std::collections::{BTreeMap, HashMap};
+
+use rand::{rngs::StdRng, seq::SliceRandom, thread_rng, SeedableRng};
+
+use crate::board::{Axes, Board};
+use crate::dictionary::Dictionary;
+use crate::grid_beehive::GridBeehive;
+use crate::hex::Hex;
+use crate::layout::Layout;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Symmetry {
+    None,
+    // around the centre of a hexagonal board
+    Rotate60,
+    Rotate120,
+    Rotate180,
+    // across the long diagonal of a square rhombus
+    Mirror,
+}
+
+impl std::str::FromStr for Symmetry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Symmetry::None),
+            "60" => Ok(Symmetry::Rotate60),
+            "120" => Ok(Symmetry::Rotate120),
+            "180" => Ok(Symmetry::Rotate180),
+            "mirror" => Ok(Symmetry::Mirror),
+            _ => Err(format!(
+                "unknown symmetry {}, expected none, 60, 120, 180 or mirror",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ShapeOptions {
+    pub rows: usize,
+    pub cols: usize,
+    pub symmetry: Symmetry,
+    // words only run along these, slots and isles follow
+    pub axes: Axes,
+    // cut the corners off into a hexagon, required by 60° and 120° rotations
+    pub hexagon: bool,
+    // share of the open cells to turn into blocks
+    pub blocks: f64,
+    pub min_len: usize,
+    // wanted share of slots by length, any mix when empty
+    pub lengths: BTreeMap<usize, f64>,
+    // shapes drawn, the closest to `lengths` wins
+    pub attempts: usize,
+    pub seed: Option<u64>,
+}
+
+impl Default for ShapeOptions {
+    fn default() -> Self {
+        Self {
+            rows: 7,
+            cols: 7,
+            symmetry: Symmetry::None,
+            axes: Axes::ALL,
+            hexagon: true,
+            blocks: 0.3,
+            min_len: 3,
+            lengths: BTreeMap::new(),
+            attempts: 200,
+            seed: None,
+        }
+    }
+}
+
+type Pos = (usize, usize);
+
+// draws block patterns with the requested symmetry, keeps the connected ones
+// whose slots are all long enough and that the dictionary has enough words for
+pub fn generate_shape(options: &ShapeOptions, dictionary: &Dictionary) -> Result<Layout, String> {
+    let (rows, cols) = (options.rows, options.cols);
+    if rows < 2 || cols < 2 {
+        return Err("a layout needs at least 2 rows and 2 cols".to_string());
+    }
+    match options.symmetry {
+        Symmetry::Rotate60 | Symmetry::Rotate120
+            if !(options.hexagon && rows == cols && rows % 2 == 1) =>
+        {
+            return Err("60° and 120° rotations need a hexagon with an odd size".to_string())
+        }
+        Symmetry::Mirror if rows != cols => {
+            return Err("mirroring needs as many rows as cols".to_string())
+        }
+        _ => {}
+    }
+
+    let mut base = GridBeehive::new(rows, cols).with_axes(options.axes);
+    if options.hexagon {
+        base.champfer_layout();
+    }
+    let orbits = orbits(&base, options.symmetry);
+    let open_cnt: usize = orbits.iter().map(|o| o.len()).sum();
+    let words_by_length = dictionary.count_by_length();
+
+    let mut rng = match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_rng(thread_rng()).unwrap(),
+    };
+    let mut best: Option<(f64, GridBeehive)> = None;
+    for _ in 0..options.attempts.max(1) {
+        let mut shape = base.clone();
+        let mut order = orbits.clone();
+        order.shuffle(&mut rng);
+
+        let mut blocked = 0;
+        for orbit in order {
+            if blocked as f64 >= options.blocks * open_cnt as f64 {
+                break;
+            }
+            for (r, c) in &orbit {
+                shape.set_cell(*r, *c, '_');
+            }
+            // blocks that leave short slots behind could be fixed by the next
+            // ones, but skipping them keeps most attempts valid
+            if short_or_lone(&shape, options.min_len) {
+                for (r, c) in &orbit {
+                    shape.set_cell(*r, *c, '\0');
+                }
+            } else {
+                blocked += orbit.len();
+            }
+        }
+
+        if short_or_lone(&shape, options.min_len) || shape.has_isles() {
+            continue;
+        }
+        let lengths = slot_lengths(&shape);
+        if lengths
+            .iter()
+            .any(|(len, cnt)| words_by_length.get(len).cloned().unwrap_or(0) < *cnt)
+        {
+            continue;
+        }
+
+        let distance = distance(&lengths, &options.lengths);
+        if best.as_ref().is_none_or(|(d, _)| distance < *d) {
+            best = Some((distance, shape));
+        }
+    }
+
+    best.map(|(_, grid)| Layout {
+        name: "generated".to_string(),
+        grid,
+        allow_adding_blacks: false,
+        locked: vec![],
+    })
+    .ok_or("no shape passed the checks, try fewer blocks or a shorter min length".to_string())
+}
+
+// open cells grouped by the symmetry, they are blocked together
+fn orbits(base: &GridBeehive, symmetry: Symmetry) -> Vec<Vec<Pos>> {
+    let (rows, cols) = (base.rows(), base.cols());
+    let center = Hex::from_cell((rows - 1) / 2, (cols - 1) / 2);
+    let rotate60 = |(r, c): Pos| -> Pos {
+        Hex::from_cell(r, c)
+            .rotate_around(center)
+            .to_cell()
+            .unwrap()
+    };
+    let map = |p: Pos| -> Pos {
+        match symmetry {
+            Symmetry::None => p,
+            Symmetry::Rotate60 => rotate60(p),
+            Symmetry::Rotate120 => rotate60(rotate60(p)),
+            Symmetry::Rotate180 => (rows - 1 - p.0, cols - 1 - p.1),
+            Symmetry::Mirror => (p.1, p.0),
+        }
+    };
+
+    let mut seen: Vec<Pos> = vec![];
+    let mut orbits = vec![];
+    for r in 0..rows {
+        for c in 0..cols {
+            if base.get_cell(r, c) == Some(&'_') || seen.contains(&(r, c)) {
+                continue;
+            }
+            let mut orbit = vec![(r, c)];
+            let mut next = map((r, c));
+            while next != (r, c) {
+                orbit.push(next);
+                next = map(next);
+            }
+            seen.extend(orbit.iter().cloned());
+            orbits.push(orbit);
+        }
+    }
+
+    orbits
+}
+
+// a slot under the minimum length, or an open cell no slot goes through
+fn short_or_lone(shape: &GridBeehive, min_len: usize) -> bool {
+    let slots = shape.slots();
+    if slots.iter().any(|slot| slot.cells.len() < min_len) {
+        return true;
+    }
+
+    (0..shape.rows())
+        .flat_map(|r| (0..shape.cols()).map(move |c| (r, c)))
+        .filter(|(r, c)| shape.get_cell(*r, *c) != Some(&'_'))
+        .any(|(r, c)| {
+            !slots.iter().any(|slot| {
+                slot.cells
+                    .iter()
+                    .any(|cell| cell.row() == r && cell.col() == c)
+            })
+        })
+}
+
+fn slot_lengths(shape: &GridBeehive) -> HashMap<usize, usize> {
+    let mut lengths = HashMap::new();
+    for slot in shape.slots() {
+        *lengths.entry(slot.cells.len()).or_default() += 1;
+    }
+
+    lengths
+}
+
+// how far the share of each slot length is from the target, 0 without one
+fn distance(lengths: &HashMap<usize, usize>, target: &BTreeMap<usize, f64>) -> f64 {
+    let total: usize = lengths.values().sum();
+    let target_total: f64 = target.values().sum();
+    if total == 0 || target_total <= 0.0 {
+        return 0.0;
+    }
+
+    let mut all: Vec<usize> = lengths.keys().chain(target.keys()).cloned().collect();
+    all.sort();
+    all.dedup();
+    all.iter()
+        .map(|len| {
+            let share = *lengths.get(len).unwrap_or(&0) as f64 / total as f64;
+            let wanted = target.get(len).unwrap_or(&0.0) / target_total;
+            (share - wanted).abs()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::{generate_shape, orbits, ShapeOptions, Symmetry};
+    use crate::board::Board;
+    use crate::dictionary::Dictionary;
+    use crate::grid_beehive::GridBeehive;
+
+    fn dictionary() -> Dictionary {
+        let words = std::fs::read_to_string("dictionaries/english/most_frequent_words").unwrap();
+        Dictionary::from_words(words.lines().map(|w| w.to_string()).collect())
+    }
+
+    #[test]
+    fn symmetric_shapes() {
+        let dictionary = dictionary();
+        for symmetry in [
+            Symmetry::None,
+            Symmetry::Rotate60,
+            Symmetry::Rotate120,
+            Symmetry::Rotate180,
+            Symmetry::Mirror,
+        ] {
+            let options = ShapeOptions {
+                symmetry,
+                seed: Some(7),
+                lengths: BTreeMap::from([(3, 0.5), (4, 0.3), (5, 0.2)]),
+                ..Default::default()
+            };
+            let layout = generate_shape(&options, &dictionary).unwrap();
+            println!("{:?}\n{}", symmetry, layout.to_text());
+
+            let grid = &layout.grid;
+            assert!(!grid.has_isles());
+            assert!(grid.slots().iter().all(|slot| slot.cells.len() >= 3));
+            for orbit in orbits(&GridBeehive::new_champfered(7, 7), symmetry) {
+                let blocked = grid.get_cell(orbit[0].0, orbit[0].1) == Some(&'_');
+                assert!(orbit
+                    .iter()
+                    .all(|(r, c)| (grid.get_cell(*r, *c) == Some(&'_')) == blocked));
+            }
+        }
+    }
+
+    #[test]
+    fn rotations_need_a_hexagon() {
+        let options = ShapeOptions {
+            rows: 6,
+            cols: 6,
+            symmetry: Symmetry::Rotate60,
+            ..Default::default()
+        };
+        assert!(generate_shape(&options, &Dictionary::from_words(vec![])).is_err());
+
+        // the 6 cells around the centre turn into each other
+        let ring = orbits(&GridBeehive::new_champfered(5, 5), Symmetry::Rotate60)
+            .into_iter()
+            .find(|orbit| orbit.contains(&(1, 2)))
+            .unwrap();
+        assert_eq!(ring.len(), 6);
+    }
+}
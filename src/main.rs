@@ -1,28 +1,80 @@
 use std::{result::Result, time::Instant};
 
+use board::Board;
 use grid_beehive::GridBeehive;
 // use regex::Regex;
 
-pub mod dictionary;
-// mod waffle;
 pub mod beehive_serde;
 pub mod beehive_swap;
+pub mod board;
+pub mod cli;
+pub mod crossword;
+pub mod dictionary;
+pub mod difficulty;
 pub mod grid;
 pub mod grid_beehive;
+pub mod hex;
+pub mod ipuz;
+pub mod layout;
+pub mod layout_generator;
+pub mod parallel;
+pub mod pdf;
+pub mod render;
+pub mod share_code;
+pub mod solver;
+pub mod spelling_bee;
+pub mod waffle;
+pub mod word_search;
+pub mod worker;
 
 use leptos::*;
 
 use crate::{
-    beehive_serde::{append_file, fetch_beehive},
+    beehive_serde::{append_file, load_beehive},
     beehive_swap::BeehiveSwap,
 };
 
 fn main() {
+    if cfg!(target_arch = "wasm32") {
+        if worker::is_worker() {
+            worker::run_worker();
+        } else {
+            mount_app();
+        }
+    } else if let Err(e) = cli::run(std::env::args().skip(1).collect()) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+// the puzzle of a mode is only fetched once the mode is picked, the resource
+// goes away with the view when another one is
+fn fetched<T, F>(what: &'static str, fetch: fn(()) -> F, show: fn(T) -> View) -> View
+where
+    T: Clone + 'static,
+    F: std::future::Future<Output = Result<T, String>> + 'static,
+{
+    let resource = create_local_resource(|| (), fetch);
+    (move || match resource.get() {
+        None => view! { <p>"Loading..."</p> }.into_view(),
+        Some(Ok(value)) => show(value),
+        Some(Err(e)) => view! { <div>"error while loading the " {what} ": " {e}</div> }.into_view(),
+    })
+    .into_view()
+}
+
+fn mount_app() {
     use beehive_swap::ui::BeehiveSwapComponent;
+    use crossword::ui::CrosswordComponent;
+    use spelling_bee::ui::SpellingBeeComponent;
+    use waffle::ui::WaffleGameComponent;
+    use word_search::ui::WordSearchComponent;
+    use worker::ui::GenerateComponent;
     // let dictionary = dictionary::Dictionary::new().unwrap();
     leptos::logging::log!("retrieving beehive");
-    let once = create_resource(|| (), fetch_beehive);
-    // let grid = GridBeehive::new(6, 6).recursive_generate(&dictionary, false).unwrap();
+    let (difficulty, set_difficulty) = create_signal::<Option<difficulty::Difficulty>>(None);
+    let once = create_resource(move || difficulty.get(), load_beehive);
+    // let grid = GridBeehive::new(6, 6).generate(&dictionary, false).unwrap();
     // let mut grid = GridBeehive::new(6, 6);
     // grid.set_row(0, "__yeah".to_string());
     // grid.set_row(1, "_h__so".to_string());
@@ -47,14 +99,55 @@ fn main() {
     // };
 
     leptos::logging::log!("grid got !");
+    let (generated, set_generated) = create_signal(None);
+    let (mode, set_mode) = create_signal("beehive".to_string());
+
     leptos::mount_to_body(move || {
         view! {
-            {move || match once.get() {
-                None => view! { <p>"Loading..."</p> }.into_view(),
-                Some(res) => match res {
-                    Ok(bh) => view! { <BeehiveSwapComponent initial_beehive=bh.clone().into()/> }.into_view(),
-                    Err(_) => view! { <div>"error while loading a beehive"</div> }.into_view(),
-                }.into_view()
+            <select on:change=move |ev| set_mode.set(event_target_value(&ev))>
+                <option value="beehive">"beehive"</option>
+                <option value="waffle">"waffle"</option>
+                <option value="crossword">"crossword"</option>
+                <option value="hex-crossword">"hex crossword"</option>
+                <option value="spelling-bee">"spelling bee"</option>
+                <option value="word-search">"word search"</option>
+            </select>
+            {move || match mode.get().as_str() {
+                "waffle" => view! { <WaffleGameComponent/> }.into_view(),
+                "crossword" => fetched("crossword", crossword::fetch_crossword, |cw| {
+                    view! { <CrosswordComponent crossword=cw/> }.into_view()
+                }),
+                "hex-crossword" => fetched("crossword", crossword::fetch_hex_crossword, |cw| {
+                    view! { <CrosswordComponent crossword=cw hex=true/> }.into_view()
+                }),
+                "spelling-bee" => fetched("spelling bee", spelling_bee::fetch_spelling_bee, |bee| {
+                    view! { <SpellingBeeComponent bee=bee/> }.into_view()
+                }),
+                "word-search" => fetched("word search", word_search::fetch_word_search, |search| {
+                    view! { <WordSearchComponent search=search/> }.into_view()
+                }),
+                _ => view! {
+                    <GenerateComponent on_generated=set_generated/>
+                    <select on:change=move |ev| {
+                        set_generated.set(None);
+                        set_difficulty.set(event_target_value(&ev).parse().ok());
+                    }>
+                        <option value="">"any difficulty"</option>
+                        {difficulty::Difficulty::ALL.iter().map(|d| view! {
+                            <option value=d.to_string()>{d.to_string()}</option>
+                        }).collect_view()}
+                    </select>
+                    {move || match generated.get() {
+                        Some(bh) => view! { <BeehiveSwapComponent initial_beehive=bh.into()/> }.into_view(),
+                        None => match once.get() {
+                            None => view! { <p>"Loading..."</p> }.into_view(),
+                            Some(res) => match res {
+                                Ok(bh) => view! { <BeehiveSwapComponent initial_beehive=bh.clone().into()/> }.into_view(),
+                                Err(e) => view! { <div>"error while loading a beehive: " {e}</div> }.into_view(),
+                            }.into_view()
+                        },
+                    }}
+                }.into_view(),
             }}
         }
     })
@@ -119,7 +212,7 @@ fn _gen_grid(rows: usize, cols: usize) -> Result<grid::Grid, ()> {
 
     let empty = grid::Grid::new(rows, cols);
 
-    let full = empty.recursive_generate(&dictionary);
+    let full = empty.generate(&dictionary);
 
     match full {
         Some(g) => Ok(g),
@@ -129,47 +222,8 @@ fn _gen_grid(rows: usize, cols: usize) -> Result<grid::Grid, ()> {
 fn _gen_grid_beehive(rows: usize, cols: usize) -> Result<grid_beehive::GridBeehive, ()> {
     let dictionary = dictionary::Dictionary::new().unwrap();
 
-    let full = match (rows, cols) {
-        (1, 1) => {
-            let mut empty = GridBeehive::new(4, 4);
-            empty.set_row(0, "n\0\0\0".to_string());
-            empty.set_row(1, "_\0_\0".to_string());
-            empty.set_row(2, "_\0\0\0".to_string());
-            empty.set_row(3, "___s".to_string());
-
-            empty.recursive_generate(&dictionary, false)
-        }
-        (1, 2) => {
-            let mut empty = GridBeehive::new(7, 4);
-            empty.set_row(0, "___n".to_string());
-            empty.set_row(1, "__\0_".to_string());
-            empty.set_row(2, "_\0\0_".to_string());
-            empty.set_row(3, "\0_\0_".to_string());
-            empty.set_row(4, "\0\0__".to_string());
-            empty.set_row(5, "\0___".to_string());
-            empty.set_row(6, "t___".to_string());
-
-            empty.recursive_generate(&dictionary, false)
-        }
-        (1, 3) => {
-            let mut empty = GridBeehive::new(4, 7);
-            empty.set_row(0, "______s".to_string());
-            empty.set_row(1, "___\0\0\0_".to_string());
-            empty.set_row(2, "__\0_\0__".to_string());
-            empty.set_row(3, "t\0\0\0___".to_string());
-
-            empty.recursive_generate(&dictionary, false)
-        }
-        (3, 4) => GridBeehive::new_343_honeycomb().recursive_generate(&dictionary, false),
-        (4, 3) => GridBeehive::new_344_honeycomb().recursive_generate(&dictionary, false),
-        (4, 4) => GridBeehive::new_444_honeycomb().recursive_generate(&dictionary, false),
-        (5, 5) => GridBeehive::new_5x5_honeycomb().recursive_generate(&dictionary, false),
-        (5, 6) => GridBeehive::new_5x6_honeycomb().recursive_generate(&dictionary, false),
-        (6, 4) => GridBeehive::new_6444_honeycomb().recursive_generate(&dictionary, true),
-        (6, 6) => GridBeehive::new_6x6_honeycomb().recursive_generate(&dictionary, true),
-        (7, 7) => GridBeehive::new_7x7_honeycomb().recursive_generate(&dictionary, false),
-        (r, c) => GridBeehive::new_spotted_champfered(r, c).recursive_generate(&dictionary, true),
-    };
+    let (empty, allow_adding_blacks) = GridBeehive::layout(rows, cols);
+    let full = empty.generate(&dictionary, allow_adding_blacks);
 
     match full {
         Some(g) => Ok(g),
@@ -0,0 +1,191 @@
+use std::{
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+use rand::random;
+
+use crate::solver::{Budget, CancelToken, Outcome, SearchOptions, SearchStats};
+
+// native only, the browser build has no threads
+#[derive(Debug, Clone)]
+pub struct ParallelOptions {
+    pub threads: usize,
+    // grids to collect before stopping
+    pub count: usize,
+    // nodes an attempt gets before it restarts with another seed, none to never restart
+    pub restart_nodes: Option<usize>,
+}
+
+impl Default for ParallelOptions {
+    fn default() -> Self {
+        Self {
+            threads: thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+            count: 1,
+            restart_nodes: None,
+        }
+    }
+}
+
+// runs independently seeded searches on a pool of threads, each of them borrowing
+// what `generate` closes over (the dictionary most of all).
+// the time budget and cancel token of `budget` apply to the whole run, the seed of
+// `search_options` seeds every attempt.
+// Solved holds the grids found even when there are fewer than asked for, other
+// outcomes mean none was found
+pub fn generate_parallel<T, F>(
+    options: &ParallelOptions,
+    budget: Budget,
+    search_options: &SearchOptions,
+    generate: F,
+) -> (Outcome<Vec<T>>, SearchStats)
+where
+    T: Send,
+    F: Fn(Budget, &SearchOptions) -> (Outcome<T>, SearchStats) + Sync,
+{
+    let start = Instant::now();
+    let base_seed = search_options.seed.unwrap_or_else(random);
+    // stops every attempt once enough grids are found or the caller cancels
+    let stop = CancelToken::default();
+
+    let found: Mutex<Vec<T>> = Mutex::new(vec![]);
+    let stats: Mutex<SearchStats> = Mutex::new(SearchStats::default());
+    let ending: Mutex<Option<Outcome<Vec<T>>>> = Mutex::new(None);
+
+    thread::scope(|scope| {
+        if let Some(cancel) = &budget.cancel {
+            let stop = stop.clone();
+            scope.spawn(move || {
+                while !stop.is_cancelled() {
+                    if cancel.is_cancelled() {
+                        stop.cancel();
+                    }
+                    thread::sleep(Duration::from_millis(10));
+                }
+            });
+        }
+
+        let threads = options.threads.max(1);
+        let mut handles = vec![];
+        for t in 0..threads {
+            let (stop, found, stats, ending, generate) =
+                (&stop, &found, &stats, &ending, &generate);
+            let budget = &budget;
+            handles.push(scope.spawn(move || {
+                let mut attempt = 0;
+                while !stop.is_cancelled() {
+                    let time = match budget.time {
+                        Some(time) => match time.checked_sub(start.elapsed()) {
+                            Some(left) => Some(left),
+                            None => {
+                                ending.lock().unwrap().get_or_insert(Outcome::Timeout);
+                                stop.cancel();
+                                break;
+                            }
+                        },
+                        None => None,
+                    };
+                    let seed = base_seed
+                        .wrapping_add((attempt * threads + t) as u64)
+                        .wrapping_mul(0x9e37_79b9_7f4a_7c15);
+                    attempt += 1;
+
+                    let (outcome, attempt_stats) = generate(
+                        Budget {
+                            time,
+                            nodes: options.restart_nodes,
+                            cancel: Some(stop.clone()),
+                        },
+                        &SearchOptions {
+                            seed: Some(seed),
+                            ..search_options.clone()
+                        },
+                    );
+                    stats.lock().unwrap().add(&attempt_stats);
+
+                    match outcome {
+                        Outcome::Solved(grid) => {
+                            let mut found = found.lock().unwrap();
+                            if found.len() < options.count {
+                                found.push(grid);
+                            }
+                            if found.len() >= options.count {
+                                stop.cancel();
+                            }
+                        }
+                        // another order won't change the answer
+                        Outcome::Exhausted => {
+                            ending.lock().unwrap().get_or_insert(Outcome::Exhausted);
+                            stop.cancel();
+                        }
+                        // a restart ran out of nodes, or the whole run out of time
+                        Outcome::Timeout => {
+                            if options.restart_nodes.is_none() {
+                                ending.lock().unwrap().get_or_insert(Outcome::Timeout);
+                                stop.cancel();
+                            }
+                        }
+                        Outcome::Cancelled => {}
+                    }
+                }
+            }));
+        }
+
+        for handle in handles {
+            let _r = handle.join();
+        }
+        // lets the cancel watcher go
+        stop.cancel();
+    });
+
+    let mut stats = stats.into_inner().unwrap();
+    stats.elapsed = start.elapsed();
+    let found = found.into_inner().unwrap();
+
+    let outcome = if !found.is_empty() {
+        Outcome::Solved(found)
+    } else {
+        ending.into_inner().unwrap().unwrap_or(Outcome::Cancelled)
+    };
+
+    (outcome, stats)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{generate_parallel, ParallelOptions};
+    use crate::board::Board;
+    use crate::dictionary::Dictionary;
+    use crate::grid_beehive::GridBeehive;
+    use crate::solver::{Budget, Outcome};
+
+    #[test]
+    fn collect_grids() {
+        let words = std::fs::read_to_string("dictionaries/english/most_frequent_words").unwrap();
+        let dictionary = Dictionary::from_words(words.lines().map(|w| w.to_string()).collect());
+        let empty = GridBeehive::new_5x5_honeycomb();
+
+        let options = ParallelOptions {
+            threads: 4,
+            count: 3,
+            restart_nodes: Some(5000),
+        };
+        let (outcome, stats) = generate_parallel(
+            &options,
+            Budget::default(),
+            &Default::default(),
+            |budget, search_options| {
+                empty.generate_within(&dictionary, false, budget, search_options)
+            },
+        );
+        println!("{}", stats);
+
+        match outcome {
+            Outcome::Solved(grids) => assert_eq!(grids.len(), 3),
+            _ => panic!("no grid found"),
+        }
+    }
+}
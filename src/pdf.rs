@@ -0,0 +1,287 @@
+use std::fmt::Write;
+
+use crate::beehive_serde::BeehiveSerde;
+use crate::beehive_swap::BeehiveSwap;
+use crate::grid_beehive::GridBeehive;
+use crate::render::{
+    drawing_size, fill_color, hex_center, hex_corners, parse_hex_color, HexDrawing,
+};
+
+// A4 in points
+const PAGE_WIDTH: f64 = 595.0;
+const PAGE_HEIGHT: f64 = 842.0;
+const MARGIN: f64 = 48.0;
+const ANSWERS_PER_PAGE: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct BookletOptions {
+    pub title: String,
+    // fill cells with the colour they start with, handy hints for beginners
+    pub colored: bool,
+}
+
+impl Default for BookletOptions {
+    fn default() -> Self {
+        Self {
+            title: "Beehive".to_string(),
+            colored: true,
+        }
+    }
+}
+
+// one scrambled puzzle per page followed by the answer key
+pub fn booklet(beehives: &[BeehiveSerde], options: &BookletOptions) -> Vec<u8> {
+    let mut pages = vec![];
+
+    for (i, beehive) in beehives.iter().enumerate() {
+        let swap: BeehiveSwap = beehive.clone().into();
+        let drawing = HexDrawing::from(&swap);
+
+        let mut page = String::new();
+        draw_text(
+            &mut page,
+            &format!("{} #{}", options.title, i + 1),
+            MARGIN,
+            PAGE_HEIGHT - MARGIN,
+            24.0,
+        );
+        draw_text(
+            &mut page,
+            "Swap letters two at a time until every line spells a word.",
+            MARGIN,
+            PAGE_HEIGHT - MARGIN - 24.0,
+            11.0,
+        );
+
+        let area_width = PAGE_WIDTH - 2.0 * MARGIN;
+        let area_top = PAGE_HEIGHT - MARGIN - 48.0;
+        let bottom = draw_hexes(
+            &mut page,
+            &drawing,
+            MARGIN,
+            area_top,
+            area_width,
+            420.0,
+            options.colored,
+        );
+
+        draw_notes(
+            &mut page,
+            MARGIN,
+            bottom - 24.0,
+            area_width,
+            bottom - 24.0 - MARGIN,
+        );
+        pages.push(page);
+    }
+
+    for (chunk_index, chunk) in beehives.chunks(ANSWERS_PER_PAGE).enumerate() {
+        let mut page = String::new();
+        draw_text(&mut page, "Answers", MARGIN, PAGE_HEIGHT - MARGIN, 24.0);
+
+        let cell_width = (PAGE_WIDTH - 3.0 * MARGIN) / 2.0;
+        let cell_height = (PAGE_HEIGHT - 3.0 * MARGIN - 24.0) / 2.0;
+        for (i, beehive) in chunk.iter().enumerate() {
+            let x = MARGIN + (i % 2) as f64 * (cell_width + MARGIN);
+            let top = PAGE_HEIGHT - 2.0 * MARGIN - 24.0 - (i / 2) as f64 * (cell_height + MARGIN);
+            let number = chunk_index * ANSWERS_PER_PAGE + i + 1;
+            draw_text(&mut page, &format!("#{}", number), x, top, 14.0);
+
+            let grid: GridBeehive = beehive.clone().into();
+            let drawing = HexDrawing::from(&grid);
+            draw_hexes(
+                &mut page,
+                &drawing,
+                x,
+                top - 12.0,
+                cell_width,
+                cell_height - 12.0,
+                false,
+            );
+        }
+        pages.push(page);
+    }
+
+    write_document(&pages)
+}
+
+// fits the grid in the box and returns the y of its bottom edge
+fn draw_hexes(
+    content: &mut String,
+    drawing: &HexDrawing,
+    left: f64,
+    top: f64,
+    max_width: f64,
+    max_height: f64,
+    colored: bool,
+) -> f64 {
+    let (unit_width, unit_height) = drawing_size(drawing.rows, drawing.cols, 1.0);
+    let size = (max_width / unit_width).min(max_height / unit_height);
+    let (width, height) = drawing_size(drawing.rows, drawing.cols, size);
+    let left = left + (max_width - width) / 2.0;
+
+    let _ = writeln!(content, "{:.2} w", size / 16.0);
+    for cell in &drawing.cells {
+        let (cx, cy) = hex_center(cell.row, cell.col, size);
+        // pdf y axis goes up
+        let (cx, cy) = (left + cx, top - cy);
+        let corners = hex_corners(cx, cy, size * 0.95);
+
+        let color = match colored {
+            true => fill_color(cell.color),
+            false => fill_color(None),
+        };
+        let [r, g, b] = parse_hex_color(color);
+        let _ = writeln!(
+            content,
+            "{:.3} {:.3} {:.3} rg",
+            r as f64 / 255.0,
+            g as f64 / 255.0,
+            b as f64 / 255.0
+        );
+        let _ = writeln!(content, "{:.2} {:.2} m", corners[0].0, corners[0].1);
+        for (x, y) in &corners[1..] {
+            let _ = writeln!(content, "{:.2} {:.2} l", x, y);
+        }
+        let _ = writeln!(content, "h B");
+
+        if let Some(letter) = cell.letter {
+            let font_size = size * 0.9;
+            let letter = letter.to_uppercase().to_string();
+            let _ = writeln!(content, "0 0 0 rg");
+            draw_text(
+                content,
+                &letter,
+                cx - text_width(&letter, font_size) / 2.0,
+                cy - font_size * 0.35,
+                font_size,
+            );
+        }
+    }
+
+    top - height
+}
+
+fn draw_notes(content: &mut String, left: f64, top: f64, width: f64, height: f64) {
+    let _ = writeln!(content, "0 0 0 rg");
+    draw_text(content, "Notes", left, top, 11.0);
+    let _ = writeln!(content, "0.7 0.7 0.7 RG 0.5 w");
+    let mut y = top - 24.0;
+    while y > top - height {
+        let _ = writeln!(
+            content,
+            "{:.2} {:.2} m {:.2} {:.2} l S",
+            left,
+            y,
+            left + width,
+            y
+        );
+        y -= 22.0;
+    }
+    let _ = writeln!(content, "0 0 0 RG");
+}
+
+fn draw_text(content: &mut String, text: &str, x: f64, y: f64, font_size: f64) {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('(', "\\(")
+        .replace(')', "\\)");
+    let _ = writeln!(
+        content,
+        "BT /F1 {:.2} Tf {:.2} {:.2} Td ({}) Tj ET",
+        font_size, x, y, escaped
+    );
+}
+
+// helvetica capitals are roughly two thirds of an em wide
+fn text_width(text: &str, font_size: f64) -> f64 {
+    text.chars()
+        .map(|c| match c {
+            'I' => 0.278,
+            'J' => 0.5,
+            'M' => 0.833,
+            'W' => 0.944,
+            _ => 0.667,
+        })
+        .sum::<f64>()
+        * font_size
+}
+
+fn write_document(pages: &[String]) -> Vec<u8> {
+    // 1: catalog, 2: page tree, 3: font, then a page and its content stream per page
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        String::new(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+    ];
+    let mut kids = vec![];
+    for page in pages {
+        let page_id = objects.len() + 1;
+        kids.push(format!("{} 0 R", page_id));
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            PAGE_WIDTH,
+            PAGE_HEIGHT,
+            page_id + 1
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            page.len(),
+            page
+        ));
+    }
+    objects[1] = format!(
+        "<< /Type /Pages /Kids [{}] /Count {} >>",
+        kids.join(" "),
+        pages.len()
+    );
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = vec![];
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        let _ = write!(pdf, "{} 0 obj\n{}\nendobj\n", i + 1, object);
+    }
+
+    let xref = pdf.len();
+    let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(pdf, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    );
+
+    pdf.into_bytes()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{booklet, BookletOptions};
+    use crate::beehive_serde::BeehiveSerde;
+
+    #[test]
+    fn write_booklet() {
+        let beehives: Vec<BeehiveSerde> = serde_json::from_str(
+            r#"[{"layout":["__yeah","_h__so","sofa_t","t_r_i_","a_ex__","the___"]},
+                {"layout":["__edit","_e__so","shoe_p","u_w_a_","n_no__","gas___"]},
+                {"layout":["__eras","_h__me","soft_e","n_a_n_","a_so__","put___"]},
+                {"layout":["__eras","_h__do","town_n","y_e_r_","p_so__","eat___"]},
+                {"layout":["__seas","_d__me","cost_t","h_a_a_","i_no__","nag___"]}]"#,
+        )
+        .unwrap();
+
+        let pdf = booklet(&beehives, &BookletOptions::default());
+        let pdf = String::from_utf8(pdf).unwrap();
+
+        assert!(pdf.starts_with("%PDF-1.4"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        // 5 puzzles and 2 pages of answers
+        assert_eq!(pdf.matches("/Type /Page ").count(), 7);
+        assert!(pdf.contains("/Count 7"));
+        assert!(pdf.contains("(Answers) Tj"));
+    }
+}
@@ -0,0 +1,433 @@
+use std::fmt::Write;
+
+use crate::beehive_swap::{BeehiveSwap, Color};
+use crate::board::Board;
+use crate::grid_beehive::GridBeehive;
+
+const SQRT_3: f64 = 1.732_050_807_568_877_2;
+
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    // circumradius of a cell, in px
+    pub cell_size: f64,
+    pub margin: f64,
+    // fill the cells with their swap colour when rendering a BeehiveSwap
+    pub colored: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 32.0,
+            margin: 8.0,
+            colored: true,
+        }
+    }
+}
+
+// what's drawn in a single hex, blocks aren't drawn at all
+#[derive(Debug, Clone)]
+pub struct HexCell {
+    pub row: usize,
+    pub col: usize,
+    pub letter: Option<char>,
+    pub color: Option<Color>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HexDrawing {
+    pub rows: usize,
+    pub cols: usize,
+    pub cells: Vec<HexCell>,
+}
+
+impl From<&GridBeehive> for HexDrawing {
+    fn from(value: &GridBeehive) -> Self {
+        let mut cells = vec![];
+        for r in 0..value.rows() {
+            for c in 0..value.cols() {
+                match value.get_cell(r, c) {
+                    Some('_') | None => {}
+                    Some(letter) => cells.push(HexCell {
+                        row: r,
+                        col: c,
+                        letter: Some(*letter).filter(|l| l != &'\0'),
+                        color: None,
+                    }),
+                }
+            }
+        }
+
+        Self {
+            rows: value.rows(),
+            cols: value.cols(),
+            cells,
+        }
+    }
+}
+
+impl From<&BeehiveSwap> for HexDrawing {
+    fn from(value: &BeehiveSwap) -> Self {
+        let mut cells = vec![];
+        for cell in value.get_cells() {
+            match value.get_shuffled_cell(&cell) {
+                Some('_') | None => {}
+                Some(letter) => cells.push(HexCell {
+                    row: cell.row(),
+                    col: cell.col(),
+                    letter: Some(*letter).filter(|l| l != &'\0'),
+                    color: Some(value.get_cell_color(&cell)),
+                }),
+            }
+        }
+
+        Self {
+            rows: value.rows(),
+            cols: value.cols(),
+            cells,
+        }
+    }
+}
+
+// pointy-top hexes, each row shifted by half a cell like the css grid does
+pub fn hex_center(row: usize, col: usize, size: f64) -> (f64, f64) {
+    let x = size * SQRT_3 * (col as f64 + row as f64 / 2.0 + 0.5);
+    let y = size * (1.5 * row as f64 + 1.0);
+
+    (x, y)
+}
+
+pub fn hex_corners(cx: f64, cy: f64, size: f64) -> [(f64, f64); 6] {
+    let mut corners = [(0.0, 0.0); 6];
+    for (i, corner) in corners.iter_mut().enumerate() {
+        let angle = std::f64::consts::PI / 180.0 * (60.0 * i as f64 - 30.0);
+        *corner = (cx + size * angle.cos(), cy + size * angle.sin());
+    }
+
+    corners
+}
+
+pub fn is_inside_hex(x: f64, y: f64, cx: f64, cy: f64, size: f64) -> bool {
+    let dx = (x - cx).abs();
+    let dy = (y - cy).abs();
+
+    dx <= size * SQRT_3 / 2.0 && dy <= size - dx / SQRT_3
+}
+
+pub fn drawing_size(rows: usize, cols: usize, size: f64) -> (f64, f64) {
+    let width = size * SQRT_3 * (cols as f64 + (rows as f64 - 1.0) / 2.0);
+    let height = size * (1.5 * (rows as f64 - 1.0) + 2.0);
+
+    (width, height)
+}
+
+pub fn fill_color(color: Option<Color>) -> &'static str {
+    match color {
+        Some(Color::Green) => "#6aaa64",
+        Some(Color::Yellow) => "#c9b458",
+        Some(Color::White) | None => "#ffffff",
+    }
+}
+
+impl HexDrawing {
+    pub fn to_svg(&self, options: &RenderOptions) -> String {
+        let size = options.cell_size;
+        let (width, height) = drawing_size(self.rows, self.cols, size);
+        let width = width + 2.0 * options.margin;
+        let height = height + 2.0 * options.margin;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{:.0}" height="{:.0}" viewBox="0 0 {:.2} {:.2}">"#,
+            width, height, width, height
+        );
+        let _ = writeln!(
+            svg,
+            r##"<rect width="100%" height="100%" fill="#eeeeee"/>"##
+        );
+        for cell in &self.cells {
+            let (cx, cy) = hex_center(cell.row, cell.col, size);
+            let (cx, cy) = (cx + options.margin, cy + options.margin);
+            let points: Vec<String> = hex_corners(cx, cy, size * 0.95)
+                .iter()
+                .map(|(x, y)| format!("{:.2},{:.2}", x, y))
+                .collect();
+            let fill = match options.colored {
+                true => fill_color(cell.color),
+                false => fill_color(None),
+            };
+            let _ = writeln!(
+                svg,
+                r##"<polygon points="{}" fill="{}" stroke="#333333" stroke-width="{:.2}"/>"##,
+                points.join(" "),
+                fill,
+                size / 16.0
+            );
+            if let Some(letter) = cell.letter {
+                let _ = writeln!(
+                    svg,
+                    r#"<text x="{:.2}" y="{:.2}" text-anchor="middle" dominant-baseline="central" font-family="Source Sans 3, sans-serif" font-size="{:.2}">{}</text>"#,
+                    cx,
+                    cy,
+                    size,
+                    letter.to_uppercase()
+                );
+            }
+        }
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+
+    pub fn to_png(&self, options: &RenderOptions) -> Vec<u8> {
+        let size = options.cell_size;
+        let (width, height) = drawing_size(self.rows, self.cols, size);
+        let width = (width + 2.0 * options.margin).ceil() as usize;
+        let height = (height + 2.0 * options.margin).ceil() as usize;
+        let mut image = Image::new(width, height, [0xee, 0xee, 0xee]);
+
+        let stroke = (size / 16.0).max(1.0);
+        for cell in &self.cells {
+            let (cx, cy) = hex_center(cell.row, cell.col, size);
+            let (cx, cy) = (cx + options.margin, cy + options.margin);
+            let outer = size * 0.95;
+            let fill = match options.colored {
+                true => parse_hex_color(fill_color(cell.color)),
+                false => parse_hex_color(fill_color(None)),
+            };
+
+            let x_min = (cx - outer).floor().max(0.0) as usize;
+            let x_max = ((cx + outer).ceil() as usize).min(width);
+            let y_min = (cy - outer).floor().max(0.0) as usize;
+            let y_max = ((cy + outer).ceil() as usize).min(height);
+            for y in y_min..y_max {
+                for x in x_min..x_max {
+                    let (px, py) = (x as f64 + 0.5, y as f64 + 0.5);
+                    if !is_inside_hex(px, py, cx, cy, outer) {
+                        continue;
+                    }
+                    if is_inside_hex(px, py, cx, cy, outer - stroke) {
+                        image.set(x, y, fill);
+                    } else {
+                        image.set(x, y, [0x33, 0x33, 0x33]);
+                    }
+                }
+            }
+
+            if let Some(letter) = cell.letter {
+                image.draw_letter(letter, cx, cy, size);
+            }
+        }
+
+        image.to_png()
+    }
+}
+
+pub(crate) fn parse_hex_color(color: &str) -> [u8; 3] {
+    let channel = |i: usize| u8::from_str_radix(&color[i..i + 2], 16).unwrap_or(0);
+
+    [channel(1), channel(3), channel(5)]
+}
+
+struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    fn new(width: usize, height: usize, background: [u8; 3]) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    fn set(&mut self, x: usize, y: usize, color: [u8; 3]) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = color;
+        }
+    }
+
+    fn draw_letter(&mut self, letter: char, cx: f64, cy: f64, size: f64) {
+        let glyph = match glyph(letter) {
+            Some(g) => g,
+            None => return,
+        };
+        let scale = ((size * 0.9) / 7.0).floor().max(1.0) as usize;
+        // near the edge the glyph starts off the image, those pixels are dropped
+        let left = cx as isize - (5 * scale / 2) as isize;
+        let top = cy as isize - (7 * scale / 2) as isize;
+
+        for (gy, bits) in glyph.iter().enumerate() {
+            for gx in 0..5 {
+                if bits & (0b10000 >> gx) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let x = usize::try_from(left + (gx * scale + dx) as isize);
+                        let y = usize::try_from(top + (gy * scale + dy) as isize);
+                        if let (Ok(x), Ok(y)) = (x, y) {
+                            self.set(x, y, [0x11, 0x11, 0x11]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // rgb, no compression: zlib stored blocks are plenty for a handful of hexes
+    fn to_png(&self) -> Vec<u8> {
+        let mut raw = vec![];
+        for y in 0..self.height {
+            raw.push(0); // no filter
+            for x in 0..self.width {
+                raw.extend_from_slice(&self.pixels[y * self.width + x]);
+            }
+        }
+
+        let mut zlib = vec![0x78, 0x01];
+        let mut blocks = raw.chunks(65_535).peekable();
+        while let Some(block) = blocks.next() {
+            let is_last = blocks.peek().is_none();
+            zlib.push(is_last as u8);
+            let len = block.len() as u16;
+            zlib.extend_from_slice(&len.to_le_bytes());
+            zlib.extend_from_slice(&(!len).to_le_bytes());
+            zlib.extend_from_slice(block);
+        }
+        zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+        let mut ihdr = vec![];
+        ihdr.extend_from_slice(&(self.width as u32).to_be_bytes());
+        ihdr.extend_from_slice(&(self.height as u32).to_be_bytes());
+        ihdr.extend_from_slice(&[8, 2, 0, 0, 0]); // 8 bits rgb
+
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        push_chunk(&mut png, b"IHDR", &ihdr);
+        push_chunk(&mut png, b"IDAT", &zlib);
+        push_chunk(&mut png, b"IEND", &[]);
+
+        png
+    }
+}
+
+fn push_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let mut chunk = kind.to_vec();
+    chunk.extend_from_slice(data);
+    png.extend_from_slice(&chunk);
+    png.extend_from_slice(&crate::share_code::crc32(&chunk).to_be_bytes());
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let mut a = 1u32;
+    let mut b = 0u32;
+    for byte in bytes {
+        a = (a + *byte as u32) % 65_521;
+        b = (b + a) % 65_521;
+    }
+
+    (b << 16) | a
+}
+
+// 5x7 bitmap capitals, one byte per row, msb on the left
+fn glyph(letter: char) -> Option<[u8; 7]> {
+    const GLYPHS: [[u8; 7]; 26] = [
+        [0x0e, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11], // A
+        [0x1e, 0x11, 0x11, 0x1e, 0x11, 0x11, 0x1e], // B
+        [0x0e, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0e], // C
+        [0x1e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1e], // D
+        [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x1f], // E
+        [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x10], // F
+        [0x0e, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0f], // G
+        [0x11, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11], // H
+        [0x0e, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e], // I
+        [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0c], // J
+        [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11], // K
+        [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f], // L
+        [0x11, 0x1b, 0x15, 0x15, 0x11, 0x11, 0x11], // M
+        [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11], // N
+        [0x0e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e], // O
+        [0x1e, 0x11, 0x11, 0x1e, 0x10, 0x10, 0x10], // P
+        [0x0e, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0d], // Q
+        [0x1e, 0x11, 0x11, 0x1e, 0x14, 0x12, 0x11], // R
+        [0x0f, 0x10, 0x10, 0x0e, 0x01, 0x01, 0x1e], // S
+        [0x1f, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // T
+        [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e], // U
+        [0x11, 0x11, 0x11, 0x11, 0x11, 0x0a, 0x04], // V
+        [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0a], // W
+        [0x11, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x11], // X
+        [0x11, 0x11, 0x0a, 0x04, 0x04, 0x04, 0x04], // Y
+        [0x1f, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1f], // Z
+    ];
+
+    let upper = letter.to_ascii_uppercase();
+    if upper.is_ascii_uppercase() {
+        Some(GLYPHS[(upper as u8 - b'A') as usize])
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{hex_center, HexDrawing, RenderOptions};
+    use crate::board::Board;
+    use crate::grid_beehive::GridBeehive;
+
+    fn yeah() -> GridBeehive {
+        let mut grid = GridBeehive::new(6, 6);
+        grid.set_row(0, "__yeah".to_string());
+        grid.set_row(1, "_h__so".to_string());
+        grid.set_row(2, "sofa_t".to_string());
+        grid.set_row(3, "t_r_i_".to_string());
+        grid.set_row(4, "a_ex__".to_string());
+        grid.set_row(5, "the___".to_string());
+
+        grid
+    }
+
+    #[test]
+    fn hex_geometry() {
+        let (x0, y0) = hex_center(0, 0, 10.0);
+        let (x1, _y1) = hex_center(0, 1, 10.0);
+        let (x2, y2) = hex_center(1, 0, 10.0);
+
+        // neighbours sit one hex width apart, next row is shifted by half of it
+        assert!((x1 - x0 - 17.32).abs() < 0.01);
+        assert!((x2 - x0 - 8.66).abs() < 0.01);
+        assert!((y2 - y0 - 15.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn render_svg() {
+        let svg = HexDrawing::from(&yeah()).to_svg(&RenderOptions::default());
+
+        assert!(svg.starts_with("<svg"));
+        // one polygon per open cell
+        assert_eq!(svg.matches("<polygon").count(), 21);
+        assert!(svg.contains(">Y</text>"));
+    }
+
+    #[test]
+    fn render_png() {
+        let png = HexDrawing::from(&yeah()).to_png(&RenderOptions::default());
+
+        assert_eq!(&png[1..4], b"PNG");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+
+        // letters wider than the margin around the cell
+        let tiny = RenderOptions {
+            cell_size: 2.0,
+            margin: 0.0,
+            ..RenderOptions::default()
+        };
+        let png = HexDrawing::from(&yeah()).to_png(&tiny);
+        assert_eq!(&png[1..4], b"PNG");
+    }
+}
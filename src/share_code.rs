@@ -0,0 +1,399 @@
+use std::fmt;
+
+use crate::beehive_serde::BeehiveSerde;
+use crate::board::{Axes, Cell, Kind};
+
+// packs a beehive into a short url-safe string, 5 bits per cell:
+// version | rows | cols | flags | layout | scramble? | locked bitmask? | crc32
+const VERSION: u8 = 1;
+const HAS_SCRAMBLE: u8 = 0b01;
+const HAS_LOCKED: u8 = 0b10;
+// axes left out, none set reads along all three like codes made before axes
+const NO_ROW: u8 = 0b100;
+const NO_COL: u8 = 0b1000;
+const NO_DIAG: u8 = 0b10000;
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShareCodeError {
+    Encoding,
+    Checksum,
+    Version(u8),
+    Truncated,
+    Layout,
+    // rows and cols take a byte each
+    TooLarge(usize, usize),
+    Character(char),
+}
+
+impl fmt::Display for ShareCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShareCodeError::Encoding => write!(
+                f,
+                "the link contains characters that aren't part of a share code"
+            ),
+            ShareCodeError::Checksum => {
+                write!(f, "the link is corrupted, its checksum doesn't match")
+            }
+            ShareCodeError::Version(v) => write!(
+                f,
+                "the link was made by an unknown version ({}) of beehive",
+                v
+            ),
+            ShareCodeError::Truncated => write!(f, "the link is incomplete"),
+            ShareCodeError::Layout => write!(f, "the link doesn't describe a valid beehive"),
+            ShareCodeError::TooLarge(rows, cols) => write!(
+                f,
+                "a {}x{} beehive is too large for a link, 255x255 at most",
+                rows, cols
+            ),
+            ShareCodeError::Character(c) => {
+                write!(f, "{:?} can't go in a link, only a-z and blocks", c)
+            }
+        }
+    }
+}
+
+pub fn encode(beehive: &BeehiveSerde) -> Result<String, ShareCodeError> {
+    let layout = beehive.layout();
+    let rows = layout.len();
+    let cols = layout.first().map(|r| r.chars().count()).unwrap_or(0);
+    let (Ok(rows_byte), Ok(cols_byte)) = (u8::try_from(rows), u8::try_from(cols)) else {
+        return Err(ShareCodeError::TooLarge(rows, cols));
+    };
+    // the code only holds one size, a ragged or mismatched board would read back
+    // as another one
+    let flatten = |grid: &Vec<String>| {
+        if grid.len() != rows || grid.iter().any(|r| r.chars().count() != cols) {
+            return Err(ShareCodeError::Layout);
+        }
+        Ok(grid.iter().flat_map(|r| r.chars()).collect::<Vec<char>>())
+    };
+    let solved = flatten(layout)?;
+    let scrambled = beehive.scrambled_rows().map(flatten).transpose()?;
+
+    let mut flags = 0;
+    if scrambled.is_some() {
+        flags |= HAS_SCRAMBLE;
+    }
+    if beehive.locked().is_some() {
+        flags |= HAS_LOCKED;
+    }
+    for (kind, bit) in [
+        (Kind::Row, NO_ROW),
+        (Kind::Col, NO_COL),
+        (Kind::Diag, NO_DIAG),
+    ] {
+        if !beehive.axes().contains(&kind) {
+            flags |= bit;
+        }
+    }
+
+    let mut bytes = vec![VERSION, rows_byte, cols_byte, flags];
+    let mut bits = BitWriter::default();
+    for c in &solved {
+        bits.push(char_to_code(*c)?, 5);
+    }
+    if let Some(scrambled) = &scrambled {
+        // blocks can't move, only letter cells are worth sending
+        for (solved_char, c) in solved.iter().zip(scrambled) {
+            if is_letter(*solved_char) {
+                bits.push(char_to_code(*c)?, 5);
+            }
+        }
+    }
+    if let Some(locked) = beehive.locked() {
+        for i in 0..solved.len() {
+            let locked = locked.contains(&Cell::new(i / cols, i % cols));
+            bits.push(locked as u8, 1);
+        }
+    }
+    bytes.append(&mut bits.into_bytes());
+    bytes.extend_from_slice(&crc32(&bytes).to_be_bytes());
+
+    Ok(base64_encode(&bytes))
+}
+
+pub fn decode(code: &str) -> Result<BeehiveSerde, ShareCodeError> {
+    let bytes = base64_decode(code.trim())?;
+    if bytes.len() < 8 {
+        return Err(ShareCodeError::Truncated);
+    }
+
+    let (payload, checksum) = bytes.split_at(bytes.len() - 4);
+    if crc32(payload).to_be_bytes() != checksum {
+        return Err(ShareCodeError::Checksum);
+    }
+    if payload[0] != VERSION {
+        return Err(ShareCodeError::Version(payload[0]));
+    }
+
+    let rows = payload[1] as usize;
+    let cols = payload[2] as usize;
+    let flags = payload[3];
+    let mut bits = BitReader::new(&payload[4..]);
+
+    let mut solved = vec![];
+    for _i in 0..(rows * cols) {
+        solved.push(code_to_char(bits.read(5)?)?);
+    }
+
+    let scrambled = if flags & HAS_SCRAMBLE != 0 {
+        let mut scrambled = vec![];
+        for c in &solved {
+            if is_letter(*c) {
+                scrambled.push(code_to_char(bits.read(5)?)?);
+            } else {
+                scrambled.push(*c);
+            }
+        }
+        Some(to_rows(&scrambled, cols))
+    } else {
+        None
+    };
+
+    let locked = if flags & HAS_LOCKED != 0 {
+        let mut locked = vec![];
+        for i in 0..(rows * cols) {
+            if bits.read(1)? == 1 {
+                locked.push(Cell::new(i / cols, i % cols));
+            }
+        }
+        Some(locked)
+    } else {
+        None
+    };
+
+    let kinds: Vec<Kind> = [
+        (Kind::Row, NO_ROW),
+        (Kind::Col, NO_COL),
+        (Kind::Diag, NO_DIAG),
+    ]
+    .into_iter()
+    .filter(|(_, bit)| flags & bit == 0)
+    .map(|(kind, _)| kind)
+    .collect();
+    if rows == 0 || cols == 0 || kinds.is_empty() {
+        return Err(ShareCodeError::Layout);
+    }
+
+    Ok(BeehiveSerde::new(to_rows(&solved, cols), scrambled, locked).with_axes(Axes::new(&kinds)))
+}
+
+fn to_rows(chars: &[char], cols: usize) -> Vec<String> {
+    chars.chunks(cols).map(|row| row.iter().collect()).collect()
+}
+
+fn is_letter(c: char) -> bool {
+    c.is_ascii_lowercase()
+}
+
+fn char_to_code(c: char) -> Result<u8, ShareCodeError> {
+    match c {
+        '\0' => Ok(0),
+        'a'..='z' => Ok(c as u8 - b'a' + 1),
+        '_' => Ok(27),
+        _ => Err(ShareCodeError::Character(c)),
+    }
+}
+
+fn code_to_char(code: u8) -> Result<char, ShareCodeError> {
+    match code {
+        0 => Ok('\0'),
+        1..=26 => Ok((b'a' + code - 1) as char),
+        27 => Ok('_'),
+        _ => Err(ShareCodeError::Layout),
+    }
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    fn push(&mut self, val: u8, width: usize) {
+        for i in (0..width).rev() {
+            if self.len / 8 == self.bytes.len() {
+                self.bytes.push(0);
+            }
+            let bit = (val >> i) & 1;
+            let last = self.bytes.len() - 1;
+            self.bytes[last] |= bit << (7 - self.len % 8);
+            self.len += 1;
+        }
+    }
+    fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+    fn read(&mut self, width: usize) -> Result<u8, ShareCodeError> {
+        let mut val = 0;
+        for _i in 0..width {
+            let byte = self
+                .bytes
+                .get(self.pos / 8)
+                .ok_or(ShareCodeError::Truncated)?;
+            let bit = (byte >> (7 - self.pos % 8)) & 1;
+            val = (val << 1) | bit;
+            self.pos += 1;
+        }
+
+        Ok(val)
+    }
+}
+
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _i in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut res = String::new();
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32;
+        for i in 0..(chunk.len() + 1) {
+            res.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+        }
+    }
+
+    res
+}
+
+fn base64_decode(code: &str) -> Result<Vec<u8>, ShareCodeError> {
+    let mut vals = vec![];
+    for c in code.bytes() {
+        let val = ALPHABET
+            .iter()
+            .position(|a| *a == c)
+            .ok_or(ShareCodeError::Encoding)?;
+        vals.push(val as u32);
+    }
+
+    let mut res = vec![];
+    for chunk in vals.chunks(4) {
+        if chunk.len() == 1 {
+            return Err(ShareCodeError::Truncated);
+        }
+        let mut n = 0;
+        for (i, val) in chunk.iter().enumerate() {
+            n |= val << (18 - 6 * i);
+        }
+        for i in 0..(chunk.len() - 1) {
+            res.push(((n >> (16 - 8 * i)) & 0xff) as u8);
+        }
+    }
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode, encode, ShareCodeError};
+    use crate::beehive_serde::BeehiveSerde;
+    use crate::beehive_swap::{layout_locked_cells, BeehiveSwap};
+    use crate::grid_beehive::GridBeehive;
+
+    fn yeah() -> BeehiveSerde {
+        BeehiveSerde::new(
+            vec![
+                "__yeah".to_string(),
+                "_h__so".to_string(),
+                "sofa_t".to_string(),
+                "t_r_i_".to_string(),
+                "a_ex__".to_string(),
+                "the___".to_string(),
+            ],
+            Some(vec![
+                "__yhae".to_string(),
+                "_s__ho".to_string(),
+                "sofa_t".to_string(),
+                "t_r_e_".to_string(),
+                "i_ax__".to_string(),
+                "the___".to_string(),
+            ]),
+            Some(layout_locked_cells(&GridBeehive::new_343_honeycomb())),
+        )
+    }
+
+    #[test]
+    fn round_trip() {
+        let code = encode(&yeah()).unwrap();
+        assert!(code.len() < 100);
+        assert!(code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+        let decoded = decode(&code).unwrap();
+        assert_eq!(decoded.layout(), yeah().layout());
+        assert_eq!(decoded.locked(), yeah().locked());
+
+        assert!(decoded.axes().is_all());
+
+        let swap: BeehiveSwap = decoded.into();
+        assert_eq!(swap.get_shuffled_row(4), Some("i_ax__".to_string()));
+
+        let two_axis = yeah().with_axes("row diag".parse().unwrap());
+        let decoded = decode(&encode(&two_axis).unwrap()).unwrap();
+        assert_eq!(decoded.axes().to_string(), "row diag");
+    }
+
+    #[test]
+    fn corrupted_code() {
+        let code = encode(&yeah()).unwrap();
+
+        let mut chars: Vec<char> = code.chars().collect();
+        chars[10] = if chars[10] == 'A' { 'B' } else { 'A' };
+        let corrupted: String = chars.into_iter().collect();
+        assert_eq!(decode(&corrupted).err(), Some(ShareCodeError::Checksum));
+
+        assert_eq!(decode("not a code!").err(), Some(ShareCodeError::Encoding));
+        assert_eq!(decode(&code[..6]).err(), Some(ShareCodeError::Truncated));
+    }
+
+    #[test]
+    fn unencodable_boards() {
+        let ragged = BeehiveSerde::new(vec!["ab".to_string(), "cde".to_string()], None, None);
+        assert_eq!(encode(&ragged).err(), Some(ShareCodeError::Layout));
+
+        let rows = vec!["ab".to_string(), "c_".to_string()];
+        let short = BeehiveSerde::new(rows.clone(), Some(vec!["ba".to_string()]), None);
+        assert_eq!(encode(&short).err(), Some(ShareCodeError::Layout));
+        let narrow = BeehiveSerde::new(rows, Some(vec!["b".to_string(), "ac_".to_string()]), None);
+        assert_eq!(encode(&narrow).err(), Some(ShareCodeError::Layout));
+
+        let wide = BeehiveSerde::new(vec!["a".repeat(256)], None, None);
+        assert_eq!(encode(&wide).err(), Some(ShareCodeError::TooLarge(1, 256)));
+
+        let accented = BeehiveSerde::new(vec!["caf\u{e9}".to_string()], None, None);
+        assert_eq!(
+            encode(&accented).err(),
+            Some(ShareCodeError::Character('\u{e9}'))
+        );
+    }
+}
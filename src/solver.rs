@@ -0,0 +1,1167 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use rand::{rngs::StdRng, seq::SliceRandom, thread_rng, Rng, SeedableRng};
+
+use crate::dictionary::{Dictionary, ForbiddenParts, WordRanks};
+
+// nodes expanded between two budget checks when running to the end
+const CHUNK_NODES: usize = 1000;
+
+// stops a running search from outside, clones share the same flag
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// limits on a search, unlimited by default
+#[derive(Debug, Clone, Default)]
+pub struct Budget {
+    pub time: Option<Duration>,
+    pub nodes: Option<usize>,
+    pub cancel: Option<CancelToken>,
+}
+
+impl Budget {
+    // what searches that spent `spent` left of it, none once it's used up
+    pub fn remaining(&self, spent: &SearchStats) -> Option<Budget> {
+        let time = match self.time.map(|time| time.checked_sub(spent.elapsed)) {
+            Some(None) | Some(Some(Duration::ZERO)) => return None,
+            time => time.flatten(),
+        };
+        let nodes = match self.nodes.map(|nodes| nodes.checked_sub(spent.nodes)) {
+            Some(None) | Some(Some(0)) => return None,
+            nodes => nodes.flatten(),
+        };
+
+        Some(Budget {
+            time,
+            nodes,
+            ..self.clone()
+        })
+    }
+}
+
+// how a search goes about it, the budget says when it stops
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    // fixes the order candidates are tried in, so that a run can be replayed
+    pub seed: Option<u64>,
+    // fills the trace of the stats, timing every check slows the search down
+    pub trace: bool,
+    // how candidates are ordered before being tried
+    pub sampling: Sampling,
+}
+
+impl SearchOptions {
+    pub fn rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_rng(thread_rng()).unwrap(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum Sampling {
+    #[default]
+    Uniform,
+    // random, but more frequent words tend to come first
+    Weighted(Arc<WordRanks>),
+    // edge lines only take the 10k most frequent words, the others are weighted
+    CommonAtEdges(Arc<WordRanks>),
+}
+
+// odds of coming first by frequency tier, rare words still show up
+const TIER_WEIGHTS: [f64; 3] = [4.0, 2.0, 1.0];
+
+impl Sampling {
+    // puts the candidate to try first at the front
+    fn order<T>(
+        &self,
+        candidates: &mut Vec<T>,
+        tier_of: impl Fn(&WordRanks, &T) -> usize,
+        edge: bool,
+        rng: &mut StdRng,
+    ) {
+        let ranks = match self {
+            Sampling::Uniform => {
+                candidates.shuffle(rng);
+                return;
+            }
+            Sampling::Weighted(ranks) => ranks,
+            Sampling::CommonAtEdges(ranks) => {
+                if edge {
+                    candidates.retain(|c| tier_of(ranks, c) == 0);
+                }
+                ranks
+            }
+        };
+
+        // weighted shuffle: the larger u^(1/weight), the earlier
+        let mut keyed: Vec<(f64, T)> = candidates
+            .drain(..)
+            .map(|c| {
+                let weight = TIER_WEIGHTS[tier_of(ranks, &c)];
+                (rng.gen::<f64>().ln() / weight, c)
+            })
+            .collect();
+        keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+        candidates.extend(keyed.into_iter().map(|(_, c)| c));
+    }
+}
+
+// candidates split by blocks are as rare as their rarest word
+fn tier(ranks: &WordRanks, text: &str) -> usize {
+    text.split('_')
+        .filter(|w| !w.is_empty())
+        .map(|w| ranks.tier(w))
+        .max()
+        .unwrap_or(0)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome<T> {
+    Solved(T),
+    // every branch was tried, there's no solution
+    Exhausted,
+    // the time or node budget ran out first
+    Timeout,
+    Cancelled,
+}
+
+impl<T> Outcome<T> {
+    pub fn solved(self) -> Option<T> {
+        match self {
+            Outcome::Solved(solution) => Some(solution),
+            _ => None,
+        }
+    }
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Outcome<U> {
+        match self {
+            Outcome::Solved(solution) => Outcome::Solved(f(solution)),
+            Outcome::Exhausted => Outcome::Exhausted,
+            Outcome::Timeout => Outcome::Timeout,
+            Outcome::Cancelled => Outcome::Cancelled,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchStats {
+    pub nodes: usize,
+    // dead ends, where a slot or line ran out of candidates
+    pub backtracks: usize,
+    // levels skipped on the way back from a dead end
+    pub backjumps: usize,
+    pub elapsed: Duration,
+    // only when the budget asks for it
+    pub trace: Option<Trace>,
+}
+
+impl SearchStats {
+    // sums up the stats of several searches, elapsed is left to the caller
+    pub fn add(&mut self, stats: &SearchStats) {
+        self.nodes += stats.nodes;
+        self.backtracks += stats.backtracks;
+        self.backjumps += stats.backjumps;
+        if let Some(trace) = &stats.trace {
+            let total = self.trace.get_or_insert_with(Default::default);
+            for (depth, &count) in trace.backtracks_per_depth.iter().enumerate() {
+                if total.backtracks_per_depth.len() <= depth {
+                    total.backtracks_per_depth.resize(depth + 1, 0);
+                }
+                total.backtracks_per_depth[depth] += count;
+            }
+            total.check_time += trace.check_time;
+            total.lookup_time += trace.lookup_time;
+            // the last solution found
+            if !trace.fill_order.is_empty() {
+                total.fill_order = trace.fill_order.clone();
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Trace {
+    // indexed by the number of slots filled when the dead end was hit
+    pub backtracks_per_depth: Vec<usize>,
+    // forward checking after each assignment
+    pub check_time: Duration,
+    // candidate lookups in the dictionary
+    pub lookup_time: Duration,
+    // how the solution was filled, empty when there's none
+    pub fill_order: Vec<FillStep>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FillStep {
+    pub slot: String,
+    // candidates the slot had left when it was picked
+    pub candidates: usize,
+}
+
+impl Trace {
+    fn backtrack_at(&mut self, depth: usize) {
+        if self.backtracks_per_depth.len() <= depth {
+            self.backtracks_per_depth.resize(depth + 1, 0);
+        }
+        self.backtracks_per_depth[depth] += 1;
+    }
+}
+
+impl fmt::Display for SearchStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} nodes, {} backtracks, {} backjumps in {:?}",
+            self.nodes, self.backtracks, self.backjumps, self.elapsed
+        )?;
+        if let Some(trace) = &self.trace {
+            write!(f, "\n{}", trace)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "checks: {:?}", self.check_time)?;
+        writeln!(f, "dictionary lookups: {:?}", self.lookup_time)?;
+        writeln!(f, "backtracks per depth:")?;
+        for (depth, cnt) in self.backtracks_per_depth.iter().enumerate() {
+            writeln!(f, "  {:>3}: {}", depth, cnt)?;
+        }
+        write!(f, "fill order:")?;
+        for (i, step) in self.fill_order.iter().enumerate() {
+            write!(
+                f,
+                "\n  {:>3}: {} ({} candidates)",
+                i + 1,
+                step.slot,
+                step.candidates
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// a search that can be run a chunk at a time, so that callers get to yield to the
+// browser or check on a cancel button in between
+pub trait Search {
+    type Solution;
+
+    // expands at most max_nodes nodes, None while there's work left
+    fn step(&mut self, max_nodes: usize) -> Option<Outcome<Self::Solution>>;
+    fn stats(&self) -> SearchStats;
+
+    fn run(&mut self) -> Outcome<Self::Solution> {
+        loop {
+            if let Some(outcome) = self.step(CHUNK_NODES) {
+                return outcome;
+            }
+        }
+    }
+}
+
+// keeps the stats and tells when the budget is spent
+#[derive(Debug, Default)]
+struct Meter {
+    budget: Budget,
+    started: Option<f64>,
+    stats: SearchStats,
+}
+
+impl Meter {
+    fn new(budget: Budget, trace: bool) -> Self {
+        let stats = SearchStats {
+            trace: trace.then(Trace::default),
+            ..Default::default()
+        };
+        Self {
+            budget,
+            stats,
+            ..Default::default()
+        }
+    }
+
+    fn trace(&mut self) -> Option<&mut Trace> {
+        self.stats.trace.as_mut()
+    }
+
+    fn check<T>(&mut self) -> Option<Outcome<T>> {
+        let now = now_ms();
+        let started = *self.started.get_or_insert(now);
+        self.stats.elapsed = Duration::from_secs_f64((now - started).max(0.0) / 1000.0);
+
+        if let Some(cancel) = &self.budget.cancel {
+            if cancel.is_cancelled() {
+                return Some(Outcome::Cancelled);
+            }
+        }
+        if let Some(time) = self.budget.time {
+            if self.stats.elapsed >= time {
+                return Some(Outcome::Timeout);
+            }
+        }
+        if let Some(nodes) = self.budget.nodes {
+            if self.stats.nodes >= nodes {
+                return Some(Outcome::Timeout);
+            }
+        }
+
+        None
+    }
+}
+
+// std's clock panics on wasm, ask the browser there
+pub(crate) fn now_ms() -> f64 {
+    if cfg!(target_arch = "wasm32") {
+        js_sys::Date::now()
+    } else {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs_f64() * 1000.0)
+            .unwrap_or(0.0)
+    }
+}
+
+// a fill problem over word slots: cells hold a letter, '\0' when still open or '_'
+// when blocked, and each slot lists the cells a word goes through in reading order
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub cells: Vec<char>,
+    pub slots: Vec<Vec<usize>>,
+}
+
+// longer nogoods rarely match again, they'd only cost memory
+const MAX_NOGOOD_LEN: usize = 6;
+
+// a slot being searched: its candidates, the slots to blame for the ones that
+// failed so far and the word currently tried
+struct Frame {
+    slot: usize,
+    candidates: Vec<u32>,
+    next: usize,
+    conflicts: Vec<usize>,
+    word: Option<(u32, usize)>,
+}
+
+// what the search loop does next
+enum Pending {
+    Descend,
+    Advance,
+    // a slot ran dry because of these slots
+    Fail(Vec<usize>),
+}
+
+// csp search: one domain of candidate words per slot, forward checking on the
+// crossing slots after each assignment and most constrained slot first.
+// failures report the slots that caused them so the search can jump straight back
+// to the culprit, and remember the assignments that failed as nogoods
+pub struct Solver {
+    problem: Problem,
+    words: Vec<Vec<u8>>,
+    domains: Vec<Vec<u32>>,
+    // per slot: (position in the slot, crossing slot, position in the crossing slot)
+    crossings: Vec<Vec<(usize, usize, usize)>>,
+    assigned: Vec<Option<u32>>,
+    // word -> slot it is assigned to
+    used: HashMap<u32, usize>,
+    // (narrowed slot, domain before, slot whose assignment narrowed it), to
+    // restore domains on backtrack and to know who pruned what
+    trail: Vec<(usize, Vec<u32>, usize)>,
+    nogoods: Vec<Vec<(usize, u32)>>,
+    // (slot, word) -> nogoods it's part of
+    nogood_index: HashMap<(usize, u32), Vec<usize>>,
+    frames: Vec<Frame>,
+    pending: Pending,
+    // how slots are called in traces
+    labels: Vec<String>,
+    // slots on the edge of the board, for Sampling::CommonAtEdges
+    edges: Vec<bool>,
+    // building the domains is the only dictionary lookup
+    lookup_time: Duration,
+    options: SearchOptions,
+    meter: Meter,
+    done: Option<Outcome<Problem>>,
+}
+
+impl Solver {
+    pub fn new(problem: Problem, dictionary: &Dictionary) -> Self {
+        let start = now_ms();
+        let mut lengths: Vec<usize> = problem.slots.iter().map(|s| s.len()).collect();
+        lengths.sort();
+        lengths.dedup();
+
+        // the same rules Board::is_invalid checks. a slot always holds a whole word,
+        // so here they only ever rule words out
+        let forbidden = ForbiddenParts::new(dictionary);
+        let mut words: Vec<Vec<u8>> = dictionary
+            .common_words
+            .iter()
+            .filter(|w| lengths.contains(&w.len()))
+            .filter(|w| w.bytes().all(|b| b.is_ascii_lowercase()))
+            .filter(|w| forbidden.is_empty() || !forbidden.spoils(w))
+            .map(|w| w.as_bytes().to_vec())
+            .collect();
+        words.sort();
+        words.dedup();
+
+        let mut by_length: HashMap<usize, Vec<u32>> = HashMap::new();
+        for (id, word) in words.iter().enumerate() {
+            by_length.entry(word.len()).or_default().push(id as u32);
+        }
+
+        let domains = problem
+            .slots
+            .iter()
+            .map(|slot| {
+                let mut domain: Vec<u32> = by_length
+                    .get(&slot.len())
+                    .cloned()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|id| {
+                        slot.iter().zip(&words[*id as usize]).all(|(cell, letter)| {
+                            let val = problem.cells[*cell];
+                            val == '\0' || val as u8 == *letter
+                        })
+                    })
+                    .collect();
+                domain.shuffle(&mut thread_rng());
+                domain
+            })
+            .collect();
+        let lookup_time = Duration::from_secs_f64((now_ms() - start).max(0.0) / 1000.0);
+
+        let mut cell_slots: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
+        for (s, slot) in problem.slots.iter().enumerate() {
+            for (pos, cell) in slot.iter().enumerate() {
+                cell_slots.entry(*cell).or_default().push((s, pos));
+            }
+        }
+        let crossings = problem
+            .slots
+            .iter()
+            .enumerate()
+            .map(|(s, slot)| {
+                slot.iter()
+                    .enumerate()
+                    .flat_map(|(pos, cell)| {
+                        cell_slots[cell]
+                            .iter()
+                            .filter(|(other, _)| *other != s)
+                            .map(move |(other, other_pos)| (pos, *other, *other_pos))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let slot_cnt = problem.slots.len();
+        Self {
+            problem,
+            words,
+            domains,
+            crossings,
+            assigned: vec![None; slot_cnt],
+            used: HashMap::new(),
+            trail: vec![],
+            nogoods: vec![],
+            nogood_index: HashMap::new(),
+            frames: vec![],
+            pending: Pending::Descend,
+            labels: (0..slot_cnt).map(|i| format!("slot {}", i)).collect(),
+            edges: vec![false; slot_cnt],
+            lookup_time,
+            options: SearchOptions::default(),
+            meter: Meter::default(),
+            done: None,
+        }
+    }
+
+    pub fn with_labels(mut self, labels: Vec<String>) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    pub fn with_edges(mut self, edges: Vec<bool>) -> Self {
+        self.edges = edges;
+        self.order_domains();
+        self
+    }
+
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.meter = Meter::new(budget, self.options.trace);
+        self
+    }
+
+    pub fn with_options(mut self, options: SearchOptions) -> Self {
+        self.meter.stats.trace = options.trace.then(Trace::default);
+        self.options = options;
+        self.order_domains();
+        self
+    }
+
+    // new() already shuffled the domains, only seeds and other policies reorder them
+    fn order_domains(&mut self) {
+        let options = &self.options;
+        if options.seed.is_none() && matches!(options.sampling, Sampling::Uniform) {
+            return;
+        }
+
+        let mut rng = options.rng();
+        let words = &self.words;
+        for (s, domain) in self.domains.iter_mut().enumerate() {
+            domain.sort();
+            options.sampling.order(
+                domain,
+                |ranks, w| tier(ranks, std::str::from_utf8(&words[*w as usize]).unwrap()),
+                self.edges[s],
+                &mut rng,
+            );
+        }
+    }
+
+    pub fn solve(mut self) -> Option<Problem> {
+        self.run().solved()
+    }
+
+    // counts the fills, up to limit, instead of stopping at the first one.
+    // with letters, the open cells have to use up exactly that multiset.
+    // nogoods and backjumps only hold while looking for a single solution, so
+    // the count backtracks chronologically
+    pub fn count(
+        mut self,
+        letters: Option<&[char]>,
+        limit: usize,
+    ) -> (Outcome<usize>, SearchStats) {
+        let mut letters = letters.map(|letters| {
+            let mut counts: HashMap<u8, isize> = HashMap::new();
+            for l in letters {
+                *counts.entry(*l as u8).or_default() += 1;
+            }
+            counts
+        });
+        let mut board = self.problem.cells.clone();
+
+        if let Some(counts) = &letters {
+            let open = board.iter().filter(|c| **c == '\0').count();
+            if open as isize != counts.values().sum::<isize>() {
+                return (Outcome::Solved(0), self.stats());
+            }
+            // words that need more of a letter than there is can't be anywhere
+            for s in 0..self.domains.len() {
+                let cells = &self.problem.slots[s];
+                let words = &self.words;
+                self.domains[s].retain(|w| {
+                    let mut needed: HashMap<u8, isize> = HashMap::new();
+                    for (cell, letter) in cells.iter().zip(&words[*w as usize]) {
+                        if board[*cell] == '\0' {
+                            *needed.entry(*letter).or_default() += 1;
+                        }
+                    }
+                    needed
+                        .iter()
+                        .all(|(l, n)| counts.get(l).is_some_and(|c| c >= n))
+                });
+            }
+        }
+
+        let mut found = 0;
+        let outcome = match self.count_from(&mut board, &mut letters, limit, &mut found) {
+            Some(Outcome::Solved(())) | None => Outcome::Solved(found),
+            Some(outcome) => outcome.map(|_| found),
+        };
+        self.meter.check::<()>();
+
+        (outcome, self.stats())
+    }
+
+    // Some when the count is over, because of the limit or the budget
+    fn count_from(
+        &mut self,
+        board: &mut Vec<char>,
+        letters: &mut Option<HashMap<u8, isize>>,
+        limit: usize,
+        found: &mut usize,
+    ) -> Option<Outcome<()>> {
+        if let Some(outcome) = self.meter.check() {
+            return Some(outcome);
+        }
+        let slot = match self.next_slot() {
+            Some(slot) => slot,
+            None => {
+                *found += 1;
+                return (*found >= limit).then_some(Outcome::Solved(()));
+            }
+        };
+        self.meter.stats.nodes += 1;
+
+        for word in self.domains[slot].clone() {
+            if self.used.contains_key(&word) {
+                continue;
+            }
+
+            // the cells this word is the first to fill
+            let placed: Vec<(usize, u8)> = self.problem.slots[slot]
+                .iter()
+                .zip(&self.words[word as usize])
+                .filter(|(cell, _)| board[**cell] == '\0')
+                .map(|(cell, letter)| (*cell, *letter))
+                .collect();
+            if let Some(counts) = letters.as_mut() {
+                for (_, letter) in &placed {
+                    *counts.entry(*letter).or_default() -= 1;
+                }
+                if counts.values().any(|n| *n < 0) {
+                    for (_, letter) in &placed {
+                        *counts.get_mut(letter).unwrap() += 1;
+                    }
+                    continue;
+                }
+            }
+            for (cell, letter) in &placed {
+                board[*cell] = *letter as char;
+            }
+
+            let mark = self.trail.len();
+            self.assigned[slot] = Some(word);
+            self.used.insert(word, slot);
+            let over = match self.forward_check(slot, word) {
+                Ok(()) => self.count_from(board, letters, limit, found),
+                Err(_) => None,
+            };
+            self.unassign(slot, word, mark);
+
+            for (cell, letter) in &placed {
+                board[*cell] = '\0';
+                if let Some(counts) = letters.as_mut() {
+                    *counts.get_mut(letter).unwrap() += 1;
+                }
+            }
+            if over.is_some() {
+                return over;
+            }
+        }
+        self.meter.stats.backtracks += 1;
+
+        None
+    }
+
+    fn finish(&mut self, outcome: Outcome<Problem>) -> Option<Outcome<Problem>> {
+        self.done = Some(outcome.clone());
+        Some(outcome)
+    }
+
+    fn solution(&self) -> Problem {
+        let mut cells = self.problem.cells.clone();
+        for (s, word) in self.assigned.iter().enumerate() {
+            if let Some(word) = word {
+                for (cell, letter) in self.problem.slots[s]
+                    .iter()
+                    .zip(&self.words[*word as usize])
+                {
+                    cells[*cell] = *letter as char;
+                }
+            }
+        }
+
+        Problem {
+            cells,
+            slots: self.problem.slots.clone(),
+        }
+    }
+
+    fn descend(&mut self) -> Option<Outcome<Problem>> {
+        let slot = match self.next_slot() {
+            Some(slot) => slot,
+            None => {
+                let fill_order = self
+                    .frames
+                    .iter()
+                    .map(|frame| FillStep {
+                        slot: self.labels[frame.slot].clone(),
+                        candidates: frame.candidates.len(),
+                    })
+                    .collect();
+                if let Some(trace) = self.meter.trace() {
+                    trace.fill_order = fill_order;
+                }
+                return Some(Outcome::Solved(self.solution()));
+            }
+        };
+        self.meter.stats.nodes += 1;
+
+        // whatever pruned this slot's domain is to blame for the missing values
+        self.frames.push(Frame {
+            slot,
+            candidates: self.domains[slot].clone(),
+            next: 0,
+            conflicts: self.pruned_by(slot),
+            word: None,
+        });
+        self.pending = Pending::Advance;
+
+        None
+    }
+
+    // tries the next candidates of the deepest slot until one passes forward checking
+    fn advance(&mut self) -> Pending {
+        let top = self.frames.len() - 1;
+        let slot = self.frames[top].slot;
+
+        while let Some(word) = self.frames[top]
+            .candidates
+            .get(self.frames[top].next)
+            .cloned()
+        {
+            self.frames[top].next += 1;
+
+            if let Some(other) = self.used.get(&word).cloned() {
+                merge(&mut self.frames[top].conflicts, &[other], slot);
+                continue;
+            }
+            if let Some(nogood) = self.matching_nogood(slot, word) {
+                merge(&mut self.frames[top].conflicts, &nogood, slot);
+                continue;
+            }
+
+            let mark = self.trail.len();
+            self.assigned[slot] = Some(word);
+            self.used.insert(word, slot);
+
+            let started = self.meter.trace().map(|_| now_ms());
+            let checked = self.forward_check(slot, word);
+            if let (Some(started), Some(trace)) = (started, self.meter.trace()) {
+                trace.check_time += Duration::from_secs_f64((now_ms() - started).max(0.0) / 1000.0);
+            }
+            match checked {
+                Ok(()) => {
+                    self.frames[top].word = Some((word, mark));
+                    return Pending::Descend;
+                }
+                Err(wiped) => {
+                    let culprits = self.pruned_by(wiped);
+                    merge(&mut self.frames[top].conflicts, &culprits, slot);
+                    self.unassign(slot, word, mark);
+                }
+            }
+        }
+
+        let frame = self.frames.pop().unwrap();
+        self.meter.stats.backtracks += 1;
+        let depth = self.frames.len();
+        if let Some(trace) = self.meter.trace() {
+            trace.backtrack_at(depth);
+        }
+        self.learn(&frame.conflicts);
+
+        Pending::Fail(frame.conflicts)
+    }
+
+    // walks back to the deepest slot in the conflict set, None when there's none left
+    fn backjump(&mut self, conflicts: Vec<usize>) -> Option<Pending> {
+        loop {
+            let frame = self.frames.last_mut()?;
+            let slot = frame.slot;
+            let (word, mark) = frame.word.take().unwrap();
+
+            if conflicts.contains(&slot) {
+                merge(&mut frame.conflicts, &conflicts, slot);
+                self.unassign(slot, word, mark);
+                return Some(Pending::Advance);
+            }
+
+            // this slot's word plays no part in the failure, skip it
+            self.unassign(slot, word, mark);
+            self.frames.pop();
+            self.meter.stats.backjumps += 1;
+        }
+    }
+
+    fn unassign(&mut self, slot: usize, word: u32, mark: usize) {
+        self.undo(mark);
+        self.used.remove(&word);
+        self.assigned[slot] = None;
+    }
+
+    // assigned slots that narrowed the domain of a slot
+    fn pruned_by(&self, slot: usize) -> Vec<usize> {
+        let mut slots: Vec<usize> = self
+            .trail
+            .iter()
+            .filter(|(narrowed, _, _)| *narrowed == slot)
+            .map(|(_, _, by)| *by)
+            .collect();
+        slots.sort();
+        slots.dedup();
+
+        slots
+    }
+
+    // a known nogood this assignment would complete, as the other slots in it
+    fn matching_nogood(&self, slot: usize, word: u32) -> Option<Vec<usize>> {
+        self.nogood_index
+            .get(&(slot, word))?
+            .iter()
+            .map(|i| &self.nogoods[*i])
+            .find(|nogood| {
+                nogood
+                    .iter()
+                    .all(|(s, w)| *s == slot || self.assigned[*s] == Some(*w))
+            })
+            .map(|nogood| nogood.iter().map(|(s, _)| *s).collect())
+    }
+
+    // the current words of the conflict set can't be completed
+    fn learn(&mut self, conflicts: &[usize]) {
+        if conflicts.is_empty() || conflicts.len() > MAX_NOGOOD_LEN {
+            return;
+        }
+
+        let nogood: Vec<(usize, u32)> = conflicts
+            .iter()
+            .filter_map(|s| self.assigned[*s].map(|w| (*s, w)))
+            .collect();
+        let id = self.nogoods.len();
+        for pair in &nogood {
+            self.nogood_index.entry(*pair).or_default().push(id);
+        }
+        self.nogoods.push(nogood);
+    }
+
+    // most constrained slot first: fewest candidates, then most open crossings
+    fn next_slot(&self) -> Option<usize> {
+        (0..self.problem.slots.len())
+            .filter(|s| self.assigned[*s].is_none())
+            .min_by_key(|s| {
+                let open_crossings = self.crossings[*s]
+                    .iter()
+                    .filter(|(_, other, _)| self.assigned[*other].is_none())
+                    .count();
+                (self.domains[*s].len(), usize::MAX - open_crossings)
+            })
+    }
+
+    // narrow the domains of the open crossing slots, the slot that ran dry if any
+    fn forward_check(&mut self, slot: usize, word: u32) -> Result<(), usize> {
+        for i in 0..self.crossings[slot].len() {
+            let (pos, other, other_pos) = self.crossings[slot][i];
+            if self.assigned[other].is_some() {
+                continue;
+            }
+
+            let letter = self.words[word as usize][pos];
+            let domain = &self.domains[other];
+            let narrowed: Vec<u32> = domain
+                .iter()
+                .filter(|w| self.words[**w as usize][other_pos] == letter)
+                .cloned()
+                .collect();
+
+            if narrowed.len() != domain.len() {
+                let previous = std::mem::replace(&mut self.domains[other], narrowed);
+                self.trail.push((other, previous, slot));
+            }
+            if self.domains[other].is_empty() {
+                return Err(other);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn undo(&mut self, mark: usize) {
+        while self.trail.len() > mark {
+            let (slot, domain, _) = self.trail.pop().unwrap();
+            self.domains[slot] = domain;
+        }
+    }
+}
+
+impl Search for Solver {
+    type Solution = Problem;
+
+    fn step(&mut self, max_nodes: usize) -> Option<Outcome<Problem>> {
+        if let Some(outcome) = &self.done {
+            return Some(outcome.clone());
+        }
+
+        let until = self.meter.stats.nodes + max_nodes;
+        loop {
+            if let Some(outcome) = self.meter.check() {
+                return self.finish(outcome);
+            }
+            if self.meter.stats.nodes >= until {
+                return None;
+            }
+
+            match std::mem::replace(&mut self.pending, Pending::Advance) {
+                Pending::Descend => {
+                    if let Some(outcome) = self.descend() {
+                        return self.finish(outcome);
+                    }
+                }
+                Pending::Advance => self.pending = self.advance(),
+                Pending::Fail(conflicts) => match self.backjump(conflicts) {
+                    Some(pending) => self.pending = pending,
+                    None => return self.finish(Outcome::Exhausted),
+                },
+            }
+        }
+    }
+
+    fn stats(&self) -> SearchStats {
+        let mut stats = self.meter.stats.clone();
+        if let Some(trace) = stats.trace.as_mut() {
+            trace.lookup_time = self.lookup_time;
+        }
+
+        stats
+    }
+}
+
+// adds the slots to a conflict set, leaving out the slot being searched
+fn merge(conflicts: &mut Vec<usize>, slots: &[usize], slot: usize) {
+    for s in slots {
+        if *s != slot && !conflicts.contains(s) {
+            conflicts.push(*s);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::{Budget, CancelToken, Outcome, Problem, Sampling, Search, SearchOptions, Solver};
+    use crate::dictionary::{Dictionary, WordRanks};
+
+    fn dictionary(words: &[&str]) -> Dictionary {
+        Dictionary::from_words(words.iter().map(|w| w.to_string()).collect())
+    }
+
+    fn square() -> Problem {
+        Problem {
+            cells: vec!['\0'; 9],
+            slots: vec![
+                vec![0, 1, 2],
+                vec![3, 4, 5],
+                vec![6, 7, 8],
+                vec![0, 3, 6],
+                vec![1, 4, 7],
+                vec![2, 5, 8],
+            ],
+        }
+    }
+
+    #[test]
+    fn solve_square() {
+        let dictionary = dictionary(&["cow", "are", "ten", "cat", "ore", "wen", "tea", "owe"]);
+        let solution = Solver::new(square(), &dictionary).solve().unwrap();
+
+        let mut words: Vec<String> = solution
+            .slots
+            .iter()
+            .map(|slot| slot.iter().map(|c| solution.cells[*c]).collect())
+            .collect();
+        words.sort();
+        words.dedup();
+        assert_eq!(words.len(), 6);
+    }
+
+    #[test]
+    fn no_duplicate_words() {
+        // bat/are/tea reads the same both ways
+        let dictionary = dictionary(&["bat", "are", "tea"]);
+        assert!(Solver::new(square(), &dictionary).solve().is_none());
+    }
+
+    #[test]
+    fn jumps_over_independent_slots() {
+        let dictionary = dictionary(&[
+            "bat", "are", "tea", "oaks", "oars", "inks", "ions", "urns", "uses", "elfs", "egos",
+        ]);
+
+        let mut alone = Solver::new(square(), &dictionary);
+        assert!(matches!(alone.run(), Outcome::Exhausted));
+
+        // four unrelated slots, two words each, get filled before the square fails
+        let mut problem = square();
+        for first in ['o', 'i', 'u', 'e'] {
+            let start = problem.cells.len();
+            problem.cells.extend([first, '\0', '\0', '\0']);
+            problem
+                .slots
+                .push(vec![start, start + 1, start + 2, start + 3]);
+        }
+        let mut solver = Solver::new(problem, &dictionary);
+        assert!(matches!(solver.run(), Outcome::Exhausted));
+        // backtracking through them would redo the square 16 times
+        assert!(solver.stats().nodes <= 2 * alone.stats().nodes + 4);
+        assert!(solver.stats().backjumps >= 4);
+    }
+
+    #[test]
+    fn budget_and_cancel() {
+        let dictionary = dictionary(&["bat", "are", "tea"]);
+
+        let budget = Budget {
+            nodes: Some(1),
+            ..Default::default()
+        };
+        let mut solver = Solver::new(square(), &dictionary).with_budget(budget);
+        assert!(matches!(solver.run(), Outcome::Timeout));
+        assert_eq!(solver.stats().nodes, 1);
+
+        let cancel = CancelToken::default();
+        let budget = Budget {
+            cancel: Some(cancel.clone()),
+            ..Default::default()
+        };
+        let mut solver = Solver::new(square(), &dictionary).with_budget(budget);
+        assert!(solver.step(1).is_none());
+        cancel.cancel();
+        assert!(matches!(solver.step(1), Some(Outcome::Cancelled)));
+        // stays finished
+        assert!(matches!(solver.step(1), Some(Outcome::Cancelled)));
+    }
+
+    #[test]
+    fn seeded_runs_repeat() {
+        let dictionary = dictionary(&["cow", "are", "ten", "cat", "ore", "wen", "tea", "owe"]);
+        let options = SearchOptions {
+            seed: Some(7),
+            ..Default::default()
+        };
+
+        let first = Solver::new(square(), &dictionary).with_options(options.clone());
+        let second = Solver::new(square(), &dictionary).with_options(options);
+        assert_eq!(first.solve().unwrap().cells, second.solve().unwrap().cells);
+    }
+
+    #[test]
+    fn trace_fill_order() {
+        let dictionary = dictionary(&["cow", "are", "ten", "cat", "ore", "wen", "tea", "owe"]);
+        let options = SearchOptions {
+            trace: true,
+            ..Default::default()
+        };
+
+        let mut solver = Solver::new(square(), &dictionary).with_options(options);
+        assert!(solver.run().solved().is_some());
+        let trace = solver.stats().trace.unwrap();
+        println!("{}", trace);
+        assert_eq!(trace.fill_order.len(), 6);
+        assert!(trace.fill_order[0].candidates >= trace.fill_order[5].candidates);
+
+        let mut solver = Solver::new(square(), &dictionary);
+        solver.run();
+        assert!(solver.stats().trace.is_none());
+    }
+
+    #[test]
+    fn count_fills() {
+        let dictionary = dictionary(&["cow", "are", "ten", "cat", "ore", "wen", "tea", "owe"]);
+        // the square reads the same across and down
+        assert!(matches!(
+            Solver::new(square(), &dictionary).count(None, 10).0,
+            Outcome::Solved(2)
+        ));
+        assert!(matches!(
+            Solver::new(square(), &dictionary).count(None, 1).0,
+            Outcome::Solved(1)
+        ));
+
+        let letters: Vec<char> = "cowaretenx".chars().collect();
+        assert!(matches!(
+            Solver::new(square(), &dictionary)
+                .count(Some(&letters[..9]), 10)
+                .0,
+            Outcome::Solved(2)
+        ));
+        assert!(matches!(
+            Solver::new(square(), &dictionary)
+                .count(Some(&letters[1..]), 10)
+                .0,
+            Outcome::Solved(0)
+        ));
+    }
+
+    #[test]
+    fn common_words_at_edges() {
+        let dictionary = dictionary(&["cow", "are", "ten", "cat", "ore", "wen", "tea", "owe"]);
+        let options = |ranks: WordRanks| SearchOptions {
+            sampling: Sampling::CommonAtEdges(Arc::new(ranks)),
+            seed: Some(3),
+            ..Default::default()
+        };
+
+        let ranks = WordRanks::from_lists("cow\nare\nten\ncat\nore\nwen", "tea\nowe");
+        let solver = Solver::new(square(), &dictionary)
+            .with_edges(vec![true; 6])
+            .with_options(options(ranks));
+        assert!(solver.solve().is_some());
+
+        // wen only comes next
+        let ranks = WordRanks::from_lists("cow\nare\nten\ncat\nore", "wen\ntea\nowe");
+        let solver = Solver::new(square(), &dictionary)
+            .with_edges(vec![true; 6])
+            .with_options(options(ranks));
+        assert!(solver.solve().is_none());
+
+        let ranks = WordRanks::from_lists("cow\nare\nten\ncat\nore", "wen\ntea\nowe");
+        let options = SearchOptions {
+            sampling: Sampling::Weighted(Arc::new(ranks)),
+            ..Default::default()
+        };
+        let solver = Solver::new(square(), &dictionary).with_options(options);
+        assert!(solver.solve().is_some());
+    }
+
+    #[test]
+    fn skips_forbidden_parts() {
+        let problem = Problem {
+            cells: vec!['\0'; 3],
+            slots: vec![vec![0, 1, 2]],
+        };
+        let mut dictionary = dictionary(&["cat", "tap", "pad"]);
+        dictionary.forbidden_tuples = vec!["at".to_string()];
+        dictionary.forbidden_ends = vec!["ad".to_string()];
+        let solution = Solver::new(problem.clone(), &dictionary).solve().unwrap();
+        assert_eq!(solution.cells, vec!['t', 'a', 'p']);
+
+        dictionary.forbidden_starts = vec!["ta".to_string()];
+        assert!(Solver::new(problem, &dictionary).solve().is_none());
+    }
+
+    #[test]
+    fn respects_prefilled_letters() {
+        let problem = Problem {
+            cells: vec!['r', '\0', '\0', '_'],
+            slots: vec![vec![0, 1, 2]],
+        };
+
+        let dictionary = dictionary(&["cat", "car", "arc", "rat", "art"]);
+        let solution = Solver::new(problem, &dictionary).solve().unwrap();
+        assert_eq!(solution.cells, vec!['r', 'a', 't', '_']);
+    }
+}
@@ -0,0 +1,488 @@
+use std::fmt;
+
+use rand::{rngs::StdRng, seq::SliceRandom, thread_rng, SeedableRng};
+
+use crate::dictionary::Dictionary;
+
+// answers are at least this long
+pub const MIN_LEN: usize = 4;
+// the letter sets picked have a reasonable number of answers, neither a handful
+// nor a whole afternoon
+const MIN_ANSWERS: usize = 15;
+const MAX_ANSWERS: usize = 60;
+// extra points for using all seven letters
+const PANGRAM_BONUS: usize = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rank {
+    Beginner,
+    GoodStart,
+    MovingUp,
+    Good,
+    Solid,
+    Nice,
+    Great,
+    Amazing,
+    Genius,
+    QueenBee,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 10] = [
+        Rank::Beginner,
+        Rank::GoodStart,
+        Rank::MovingUp,
+        Rank::Good,
+        Rank::Solid,
+        Rank::Nice,
+        Rank::Great,
+        Rank::Amazing,
+        Rank::Genius,
+        Rank::QueenBee,
+    ];
+
+    // the share of the maximum score a rank starts at, in percent
+    fn percent(&self) -> usize {
+        match self {
+            Rank::Beginner => 0,
+            Rank::GoodStart => 2,
+            Rank::MovingUp => 5,
+            Rank::Good => 8,
+            Rank::Solid => 15,
+            Rank::Nice => 25,
+            Rank::Great => 40,
+            Rank::Amazing => 50,
+            Rank::Genius => 70,
+            Rank::QueenBee => 100,
+        }
+    }
+
+    // rounded like the scores are shown, so a rank is never out of reach
+    pub fn min_score(&self, max_score: usize) -> usize {
+        (max_score * self.percent() + 50) / 100
+    }
+
+    pub fn from_score(score: usize, max_score: usize) -> Self {
+        Rank::ALL
+            .into_iter()
+            .rev()
+            .find(|r| score >= r.min_score(max_score))
+            .unwrap_or(Rank::Beginner)
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rank::Beginner => write!(f, "beginner"),
+            Rank::GoodStart => write!(f, "good start"),
+            Rank::MovingUp => write!(f, "moving up"),
+            Rank::Good => write!(f, "good"),
+            Rank::Solid => write!(f, "solid"),
+            Rank::Nice => write!(f, "nice"),
+            Rank::Great => write!(f, "great"),
+            Rank::Amazing => write!(f, "amazing"),
+            Rank::Genius => write!(f, "genius"),
+            Rank::QueenBee => write!(f, "queen bee"),
+        }
+    }
+}
+
+// why a submitted word doesn't count
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rejection {
+    TooShort,
+    MissingCentre,
+    BadLetter(char),
+    NotInList,
+    AlreadyFound,
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::TooShort => write!(f, "too short"),
+            Rejection::MissingCentre => write!(f, "missing the centre letter"),
+            Rejection::BadLetter(l) => write!(f, "{} isn't one of the letters", l),
+            Rejection::NotInList => write!(f, "not in the word list"),
+            Rejection::AlreadyFound => write!(f, "already found"),
+        }
+    }
+}
+
+// seven letters around a centre one, the answers are worked out up front from
+// the dictionary
+#[derive(Debug, Clone)]
+pub struct SpellingBee {
+    centre: char,
+    outer: Vec<char>,
+    answers: Vec<String>,
+    found: Vec<String>,
+}
+
+impl SpellingBee {
+    // the answers are the common words, the ones players know
+    pub fn new(centre: char, outer: &str, dictionary: &Dictionary) -> Result<Self, String> {
+        // the ring is drawn as it is, so it needs exactly six letters of its own
+        let mut letters: Vec<char> = outer.chars().chain([centre]).collect();
+        letters.sort();
+        letters.dedup();
+        if outer.chars().count() != 6
+            || letters.len() != 7
+            || !letters.iter().all(|l| l.is_ascii_lowercase())
+        {
+            return Err(format!(
+                "expected a centre letter and 6 other different lowercase letters, got {}{}",
+                centre, outer
+            ));
+        }
+
+        let mut answers: Vec<String> = dictionary
+            .common_words
+            .iter()
+            .filter(|w| is_answer(w, centre, &letters))
+            .cloned()
+            .collect();
+        answers.sort();
+        answers.dedup();
+
+        let bee = Self {
+            centre,
+            outer: outer.chars().collect(),
+            answers,
+            found: vec![],
+        };
+        if !bee.answers.iter().any(|w| bee.is_pangram(w)) {
+            return Err(format!("no word uses all of {}{}", centre, outer));
+        }
+
+        Ok(bee)
+    }
+
+    // letter sets come from the pangrams, tried in random order with each centre
+    pub fn generate(dictionary: &Dictionary, seed: Option<u64>) -> Option<Self> {
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_rng(thread_rng()).unwrap(),
+        };
+        // an s makes every plural an answer
+        let mut sets: Vec<Vec<char>> = dictionary
+            .common_words
+            .iter()
+            .filter(|w| w.chars().all(|c| c.is_ascii_lowercase() && c != 's'))
+            .map(|w| {
+                let mut letters: Vec<char> = w.chars().collect();
+                letters.sort();
+                letters.dedup();
+                letters
+            })
+            .filter(|letters| letters.len() == 7)
+            .collect();
+        sets.sort();
+        sets.dedup();
+        sets.shuffle(&mut rng);
+
+        for mut letters in sets {
+            letters.shuffle(&mut rng);
+            for i in 0..letters.len() {
+                let outer: String = letters
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, l)| *l)
+                    .collect();
+                if let Ok(bee) = Self::new(letters[i], &outer, dictionary) {
+                    if (MIN_ANSWERS..=MAX_ANSWERS).contains(&bee.answers.len()) {
+                        return Some(bee);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    pub fn centre(&self) -> char {
+        self.centre
+    }
+    pub fn outer(&self) -> &Vec<char> {
+        &self.outer
+    }
+    pub fn answers(&self) -> &Vec<String> {
+        &self.answers
+    }
+    // in the order they were found
+    pub fn found(&self) -> &Vec<String> {
+        &self.found
+    }
+
+    pub fn is_pangram(&self, word: &str) -> bool {
+        word.contains(self.centre) && self.outer.iter().all(|l| word.contains(*l))
+    }
+
+    // a point for the shortest words, a point a letter past them
+    pub fn word_score(&self, word: &str) -> usize {
+        let len = word.chars().count();
+        let score = if len == MIN_LEN { 1 } else { len };
+        match self.is_pangram(word) {
+            true => score + PANGRAM_BONUS,
+            false => score,
+        }
+    }
+    pub fn score(&self) -> usize {
+        self.found.iter().map(|w| self.word_score(w)).sum()
+    }
+    pub fn max_score(&self) -> usize {
+        self.answers.iter().map(|w| self.word_score(w)).sum()
+    }
+    pub fn rank(&self) -> Rank {
+        Rank::from_score(self.score(), self.max_score())
+    }
+    pub fn is_complete(&self) -> bool {
+        self.found.len() == self.answers.len()
+    }
+
+    // the points the word scored, or why it didn't
+    pub fn submit(&mut self, word: &str) -> Result<usize, Rejection> {
+        let word = word.trim().to_lowercase();
+        if let Some(l) = word
+            .chars()
+            .find(|l| *l != self.centre && !self.outer.contains(l))
+        {
+            return Err(Rejection::BadLetter(l));
+        }
+        if word.chars().count() < MIN_LEN {
+            return Err(Rejection::TooShort);
+        }
+        if !word.contains(self.centre) {
+            return Err(Rejection::MissingCentre);
+        }
+        if self.found.contains(&word) {
+            return Err(Rejection::AlreadyFound);
+        }
+        if self.answers.binary_search(&word).is_err() {
+            return Err(Rejection::NotInList);
+        }
+
+        let score = self.word_score(&word);
+        self.found.push(word);
+
+        Ok(score)
+    }
+
+    // only the outer letters move, the centre stays put
+    pub fn shuffle(&mut self) {
+        self.outer.shuffle(&mut thread_rng());
+    }
+}
+
+fn is_answer(word: &str, centre: char, letters: &[char]) -> bool {
+    word.chars().count() >= MIN_LEN
+        && word.contains(centre)
+        && word.chars().all(|l| letters.contains(&l))
+}
+
+impl fmt::Display for SpellingBee {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let letter = |l: &char| l.to_uppercase().to_string();
+        let outer: Vec<String> = self.outer.iter().map(letter).collect();
+        writeln!(f, "spelling bee around {}", letter(&self.centre))?;
+        writeln!(f, "  {} {}", outer[0], outer[1])?;
+        writeln!(f, " {} {} {}", outer[2], letter(&self.centre), outer[3])?;
+        writeln!(f, "  {} {}", outer[4], outer[5])?;
+        writeln!(
+            f,
+            "{} answers, {} points, rank {} with {}",
+            self.answers.len(),
+            self.max_score(),
+            self.rank(),
+            self.score()
+        )
+    }
+}
+
+// the browser build has no dictionary folder, the common words are served next
+// to the app
+pub async fn fetch_spelling_bee(_c: ()) -> Result<SpellingBee, String> {
+    let text = reqwasm::http::Request::get("/most_frequent_words")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+    let dictionary = Dictionary::from_words(text.lines().map(|w| w.to_string()).collect());
+
+    SpellingBee::generate(&dictionary, None).ok_or("no letters to play with".to_string())
+}
+
+pub mod ui {
+    use leptos::*;
+
+    use super::*;
+
+    // the seven letters drawn as a little honeycomb, the centre in the middle row
+    #[component]
+    pub fn SpellingBeeComponent(bee: SpellingBee) -> impl IntoView {
+        let (bee, set_bee) = create_signal(bee);
+        let (word, set_word) = create_signal(String::new());
+        let (message, set_message) = create_signal::<Option<String>>(None);
+
+        let add = move |l: char| {
+            set_word.update(|w| w.push(l));
+            set_message.set(None);
+        };
+        let enter = move || {
+            let submitted = word.get();
+            let mut result = Err(Rejection::TooShort);
+            set_bee.update(|b| result = b.submit(&submitted));
+            set_message.set(Some(match result {
+                Ok(score) if bee.with(|b| b.is_pangram(&submitted)) => {
+                    format!("pangram! +{}", score)
+                }
+                Ok(score) => format!("+{}", score),
+                Err(rejection) => rejection.to_string(),
+            }));
+            set_word.set(String::new());
+        };
+        let on_key = move |ev: ev::KeyboardEvent| {
+            let key = ev.key();
+            match key.as_str() {
+                "Enter" => enter(),
+                "Backspace" => set_word.update(|w| {
+                    w.pop();
+                }),
+                _ => {
+                    let mut chars = key.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(l), None) if l.is_ascii_alphabetic() => add(l.to_ascii_lowercase()),
+                        _ => return,
+                    }
+                }
+            }
+            ev.prevent_default();
+        };
+
+        let cell = move |at: Option<usize>| {
+            let letter = move || {
+                bee.with(|b| match at {
+                    Some(i) => b.outer()[i],
+                    None => b.centre(),
+                })
+            };
+            view! {
+                <div
+                    class="cell"
+                    class:is-centre=at.is_none()
+                    on:click=move |_| add(letter())
+                >
+                    {move || letter().to_uppercase().to_string()}
+                </div>
+            }
+        };
+        let offset = || view! { <div class="offset" /> };
+        let found = move || {
+            let mut found = bee.with(|b| b.found().clone());
+            found.sort();
+            found
+        };
+
+        view! {
+            <div class="spelling-bee" tabindex="0" on:keydown=on_key>
+                <div class="bee-word">{move || word.get().to_uppercase()}</div>
+                <div
+                    class="beehive-container"
+                    style:width="6em"
+                    style:grid-template-columns="repeat(6, minmax(0, 1fr))"
+                >
+                    {offset()} {cell(Some(0))} {cell(Some(1))} {offset()}
+                    {cell(Some(2))} {cell(None)} {cell(Some(3))}
+                    {offset()} {cell(Some(4))} {cell(Some(5))} {offset()}
+                </div>
+                <button on:click=move |_| set_word.update(|w| {
+                    w.pop();
+                })>"Delete"</button>
+                <button on:click=move |_| set_bee.update(|b| b.shuffle())>"Shuffle"</button>
+                <button on:click=move |_| enter()>"Enter"</button>
+                <div>{move || message.get()}</div>
+            </div>
+            <div>
+                {move || bee.with(|b| format!(
+                    "{}: {} of {} points, next rank at {}",
+                    b.rank(),
+                    b.score(),
+                    b.max_score(),
+                    Rank::ALL
+                        .into_iter()
+                        .find(|r| r > &b.rank())
+                        .map(|r| r.min_score(b.max_score()))
+                        .unwrap_or(b.max_score()),
+                ))}
+            </div>
+            <div>
+                {move || format!("{} of {} words", found().len(), bee.with(|b| b.answers().len()))}
+                <ul class="found-words">
+                    {move || found().into_iter().map(|w| {
+                        let pangram = bee.with(|b| b.is_pangram(&w));
+                        view! { <li class:is-pangram=pangram>{w}</li> }
+                    }).collect_view()}
+                </ul>
+            </div>
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Rank, Rejection, SpellingBee};
+    use crate::dictionary::Dictionary;
+
+    fn dictionary() -> Dictionary {
+        let words = std::fs::read_to_string("dictionaries/english/most_frequent_words").unwrap();
+        Dictionary::from_words(words.lines().map(|w| w.to_string()).collect())
+    }
+
+    #[test]
+    fn generate_with_a_pangram() {
+        let dictionary = dictionary();
+        let bee = SpellingBee::generate(&dictionary, Some(5)).unwrap();
+        println!("{}", bee);
+        assert!(bee.answers().iter().any(|w| bee.is_pangram(w)));
+        for word in bee.answers() {
+            assert!(word.len() >= 4 && word.contains(bee.centre()));
+            assert!(dictionary.common_words.contains(word));
+        }
+
+        assert!(SpellingBee::new('a', "bcdefg", &dictionary).is_err());
+        assert!(SpellingBee::new('a', "aklngt", &dictionary).is_err());
+        assert!(SpellingBee::new('i', "talkngi", &dictionary).is_err());
+        assert!(SpellingBee::new('i', "talkng", &dictionary).is_ok());
+    }
+
+    #[test]
+    fn score_and_rank() {
+        let mut bee = SpellingBee::new('i', "talkng", &dictionary()).unwrap();
+        assert_eq!(bee.rank(), Rank::Beginner);
+
+        assert_eq!(bee.submit("talk"), Err(Rejection::MissingCentre));
+        assert_eq!(bee.submit("kin"), Err(Rejection::TooShort));
+        assert_eq!(bee.submit("king"), Ok(1));
+        assert_eq!(bee.submit("KING"), Err(Rejection::AlreadyFound));
+        assert_eq!(bee.submit("kings"), Err(Rejection::BadLetter('s')));
+        assert_eq!(bee.submit("ngik"), Err(Rejection::NotInList));
+        assert_eq!(bee.submit("talking"), Ok(7 + 7));
+        assert_eq!(bee.score(), 15);
+        assert_eq!(
+            bee.found(),
+            &vec!["king".to_string(), "talking".to_string()]
+        );
+
+        for word in bee.answers().clone() {
+            let _ = bee.submit(&word);
+        }
+        assert!(bee.is_complete());
+        assert_eq!(bee.score(), bee.max_score());
+        assert_eq!(bee.rank(), Rank::QueenBee);
+        assert_eq!(Rank::from_score(49, 100), Rank::Great);
+    }
+}
@@ -1,228 +1,245 @@
-use std::{result::Result, collections::HashSet};
 use std::fmt;
 
-use rand::{thread_rng, seq::SliceRandom};
-
+use crate::beehive_swap::Swap;
+use crate::board::{Board, Cell};
 use crate::dictionary::Dictionary;
+use crate::grid::Grid;
+use crate::solver::{Budget, Outcome, SearchOptions, SearchStats};
 
-struct Waffle {
-    top_word: String,
-    bottom_word: String,
-    left_word: String,
-    right_word: String,
-    horizontal_word: String,
-    vertical_word: String,
-}
+// the square take on the swap puzzle, words across and down a grid with holes
+pub type WaffleSwap = Swap<Grid>;
 
-impl Waffle {
-    pub fn generate(dictionary: Dictionary) -> Result<Self, ()> {
-        let top_candidates = &dictionary.find_candidates(".....".to_string())?;
-        let mut top_candidates = top_candidates.clone();
-
-        top_candidates.shuffle(&mut thread_rng());
-        let mut top_candidates = top_candidates.into_iter();
-
-        while let Some(top_candidate) = top_candidates.next() {
-            // left word
-            let left_word_pattern = format!("{}....", &top_candidate.chars().nth(0).unwrap());
-            let left_candidates = &dictionary.find_candidates(left_word_pattern.to_string())?;
-            let mut left_candidates = left_candidates.clone();
-            left_candidates.shuffle(&mut thread_rng());
-            let mut left_candidates = left_candidates.into_iter();
-
-            while let Some(left_candidate) = left_candidates.next() {
-                //  horizontal word
-                let horizontal_word_pattern = format!("{}....", &left_candidate.chars().nth(2).unwrap());
-                let horizontal_candidates = &dictionary.find_candidates(horizontal_word_pattern.to_string())?;
-                let mut horizontal_candidates = horizontal_candidates.clone();
-                horizontal_candidates.shuffle(&mut thread_rng());
-                let mut horizontal_candidates = horizontal_candidates.into_iter();
-
-                while let Some(horizontal_candidate) = horizontal_candidates.next() {
-                    //  vertical word
-                    let vertical_word_pattern = format!("{}.{}..", &top_candidate.chars().nth(2).unwrap(), &horizontal_candidate.chars().nth(2).unwrap());
-                    let vertical_candidates = &dictionary.find_candidates(vertical_word_pattern.to_string())?;
-                    let mut vertical_candidates = vertical_candidates.clone();
-                    vertical_candidates.shuffle(&mut thread_rng());
-                    let mut vertical_candidates = vertical_candidates.into_iter();
-
-                    while let Some(vertical_candidate) = vertical_candidates.next() {
-                        //  bottom word
-                        let bottom_word_pattern = format!("{}.{}..", &left_candidate.chars().nth(4).unwrap(), &vertical_candidate.chars().nth(4).unwrap());
-                        let bottom_candidates = &dictionary.find_candidates(bottom_word_pattern.to_string())?;
-                        let mut bottom_candidates = bottom_candidates.clone();
-                        bottom_candidates.shuffle(&mut thread_rng());
-                        let mut bottom_candidates = bottom_candidates.into_iter();
-
-                        while let Some(bottom_candidate) = bottom_candidates.next() {
-                            // right word
-                            let right_word_pattern = format!("{}.{}.{}", &top_candidate.chars().nth(4).unwrap(), &horizontal_candidate.chars().nth(4).unwrap(), &bottom_candidate.chars().nth(4).unwrap());
-                            let right_candidates = &dictionary.find_candidates(right_word_pattern.to_string())?;
-                            let mut right_candidates = right_candidates.clone();
-                            right_candidates.shuffle(&mut thread_rng());
-                            let mut right_candidates = right_candidates.into_iter();
-
-                            if let Some(right_candidate) = right_candidates.next() {
-
-                                let waffle = Self {
-                                    top_word: top_candidate,
-                                    left_word: left_candidate,
-                                    bottom_word: bottom_candidate,
-                                    right_word: right_candidate,
-                                    horizontal_word: horizontal_candidate,
-                                    vertical_word: vertical_candidate,
-                                };
-
-                                return Ok(waffle);
-                            }
-                        }
-                    }
-                }
-            }
-        }
+// sizes with a word on every other row and col
+pub const SIZES: [usize; 2] = [5, 7];
 
-        Err(())
+impl From<Grid> for WaffleSwap {
+    fn from(value: Grid) -> Self {
+        let locked_cells = waffle_locked_cells(value.rows());
+        Swap::new(value, locked_cells)
     }
+}
 
+// the corners and the centre are given away, like the stock beehive cells
+pub fn waffle_locked_cells(size: usize) -> Vec<Cell> {
+    let last = size - 1;
+    vec![
+        Cell::new(0, 0),
+        Cell::new(0, last),
+        Cell::new(last / 2, last / 2),
+        Cell::new(last, 0),
+        Cell::new(last, last),
+    ]
 }
 
-#[derive(Debug, Clone)]
-struct Waffle2 {
-    words: Vec<String>,
+// the holes never move, only the letters are searched for
+pub fn generate_waffle(
+    size: usize,
+    dictionary: &Dictionary,
+    budget: Budget,
+    options: &SearchOptions,
+) -> (Outcome<Grid>, SearchStats) {
+    Board::generate_within(&Grid::new_waffle(size), dictionary, false, budget, options)
 }
-impl Waffle2 {
-    pub fn is_complete(&self) -> bool {
-        self.words.len() == 8usize
-    }
 
-    pub fn has_duplicates(&self) -> bool {
-        let iter = self.words.clone().into_iter();
-        let words_set: HashSet<String>  = HashSet::from_iter(iter);
+impl fmt::Display for WaffleSwap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "waffle-swap of size {}x{}", self.rows(), self.cols())?;
+        for (name, grid) in [
+            ("Solved layout", self.solved_grid()),
+            ("Shuffled layout", self.shuffled_grid()),
+        ] {
+            writeln!(f, "{}", name)?;
+            for row in grid.to_rows() {
+                let cells: Vec<String> = row
+                    .chars()
+                    .map(|c| c.to_uppercase().to_string().replace('_', " "))
+                    .collect();
+                writeln!(f, "{}", cells.join(" "))?;
+            }
+        }
 
-        words_set.len() < self.words.len()
+        Ok(())
     }
+}
 
-    pub fn generate_pattern(&self) -> String {
-        let pattern = match &self.words.len() {
-            0 =>         ".......".to_string(),
-            1 => format!("{}......",    &self.words[0].chars().nth(0).unwrap()),
-            2 => format!("{}......",    &self.words[1].chars().nth(2).unwrap()),
-            3 => format!("{}.{}....",   &self.words[0].chars().nth(2).unwrap(), &self.words[2].chars().nth(2).unwrap()),
-            4 => format!("{}.{}....",   &self.words[1].chars().nth(4).unwrap(), &self.words[3].chars().nth(4).unwrap()),
-            5 => format!("{}.{}.{}..",  &self.words[0].chars().nth(4).unwrap(), &self.words[2].chars().nth(4).unwrap(), &self.words[4].chars().nth(4).unwrap()),
-            6 => format!("{}.{}.{}..",  &self.words[1].chars().nth(6).unwrap(), &self.words[3].chars().nth(6).unwrap(), &self.words[5].chars().nth(6).unwrap()),
-            7 => format!("{}.{}.{}.{}", &self.words[0].chars().nth(6).unwrap(), &self.words[2].chars().nth(6).unwrap(), &self.words[4].chars().nth(6).unwrap(), &self.words[6].chars().nth(6).unwrap()),
-            _ =>         ".......".to_string(),
+pub mod ui {
+    use leptos::*;
+    use rand::random;
+
+    use super::*;
+    use crate::beehive_swap::Color;
+    use crate::worker::{GenerationWorker, WorkerMessage};
+
+    #[component]
+    pub fn WaffleSwapComponent(initial_waffle: WaffleSwap) -> impl IntoView {
+        let (waffle, set_waffle) = create_signal(initial_waffle);
+        let (candidate, swap) = create_signal::<Option<Cell>>(None);
+        let (cnt, cnt_set) = create_signal(0);
+        let words = create_local_resource(|| (), crate::dictionary::fetch_word_set);
+        // until the words are there only the stored solution wins
+        let won = move || {
+            waffle.with(|w| {
+                words.with(|words| match words {
+                    Some(Ok(words)) => w.is_won(words),
+                    _ => w.is_solved(),
+                })
+            })
         };
 
-        pattern
-    }
-
-    pub fn recursive_generate(dictionary: &Dictionary, waffle: Waffle2) -> Option<Waffle2> {
-        // exit conditions
-        if waffle.has_duplicates() {
-            println!("disqualified waffle for duplicate word {:?}", &waffle);
-            return None;
-        }
-        if waffle.is_complete() {
-            return Some(waffle)
+        let size = waffle.with(|w| w.rows());
+        view! {
+            <div
+                class="waffle-container"
+                style:grid-template-columns = format!("repeat({}, 1.5em)", size)
+            >
+                {
+                    (0..size).flat_map(|r| (0..size).map(move |c| Cell::new(r, c))).map(move |cell| {
+                        let letter = move || waffle.with(|w| *w.get_shuffled_cell(&cell).unwrap());
+                        let color = move || waffle.with(|w| w.get_cell_color(&cell));
+
+                        match letter() {
+                            '_' => view! { <div class="hole" /> },
+                            _ => view! {
+                                <div
+                                    on:click=move |_| {
+                                        if color() == Color::Green || won() {
+                                            return;
+                                        }
+                                        match candidate.get() {
+                                            None => swap.set(Some(cell)),
+                                            Some(cell_a) => {
+                                                if cell_a != cell {
+                                                    set_waffle.update(|w| w.swap(&cell_a, &cell));
+                                                    cnt_set.update(|val| *val += 1);
+                                                }
+                                                swap.set(None);
+                                            }
+                                        }
+                                    }
+                                    class="square"
+                                    class:is-green = move || color() == Color::Green || won()
+                                    class:is-yellow = move || color() == Color::Yellow
+                                    class:is-swap = move || candidate.get() == Some(cell)
+                                >
+                                    {move || letter().to_uppercase().to_string()}
+                                </div>
+                            },
+                        }
+                    }).collect_view()
+                }
+            </div>
+            <div>
+                "Swap counter: " {cnt}
+            </div>
+            {move || won().then(|| view! { <div>"Solved in " {cnt} " swaps!"</div> })}
         }
-
-        let pattern = waffle.generate_pattern();
-        let candidates = &dictionary.find_candidates(pattern).unwrap_or(vec![]);
-        let mut candidates = candidates.clone();
-
-        candidates.shuffle(&mut thread_rng());
-        let mut candidates = candidates.into_iter();
-
-        while let Some(candidate) = candidates.next() {
-            let mut incr_waffle = waffle.clone();
-            incr_waffle.words.push(candidate);
-            if let Some(solved_waffle) = Waffle2::recursive_generate(dictionary, incr_waffle) {
-                return Some(solved_waffle);
-            }
-        };
-
-        None
     }
 
-    pub fn generate(dictionary: &Dictionary) -> Option<Waffle2> {
-        let empty_waffle = Waffle2 {
-            words: vec![]
+    // waffles are filled in the worker like beehives, there is no stored set of them
+    #[component]
+    pub fn WaffleGameComponent() -> impl IntoView {
+        let (size, set_size) = create_signal(SIZES[0]);
+        let (waffle, set_waffle) = create_signal::<Option<WaffleSwap>>(None);
+        let (progress, set_progress) = create_signal::<Option<usize>>(None);
+        let (error, set_error) = create_signal::<Option<String>>(None);
+        let worker = store_value::<Option<GenerationWorker>>(None);
+
+        let start = move |_| {
+            set_error.set(None);
+            set_progress.set(Some(0));
+
+            let spawned = GenerationWorker::spawn(
+                Grid::new_waffle(size.get()),
+                false,
+                random(),
+                move |message| match message {
+                    WorkerMessage::Progress { nodes, .. } => set_progress.set(Some(nodes)),
+                    WorkerMessage::Done(layout) => {
+                        set_progress.set(None);
+                        worker.with_value(|w| w.as_ref().map(|w| w.cancel()));
+                        let grid: Grid = layout.into();
+                        set_waffle.set(Some(grid.into()));
+                    }
+                    WorkerMessage::Failed(e) => {
+                        set_progress.set(None);
+                        worker.with_value(|w| w.as_ref().map(|w| w.cancel()));
+                        set_error.set(Some(e));
+                    }
+                },
+            );
+            match spawned {
+                Ok(w) => worker.set_value(Some(w)),
+                Err(e) => {
+                    set_progress.set(None);
+                    set_error.set(Some(e));
+                }
+            }
         };
 
-        Waffle2::recursive_generate(dictionary, empty_waffle)
-    }
-}
-impl fmt::Display for Waffle {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}\n\
-        {} {} {}\n\
-        {}\n\
-        {} {} {}\n\
-        {}",
-        self.top_word,
-        self.left_word.chars().nth(1).unwrap(), self.vertical_word.chars().nth(1).unwrap(), self.right_word.chars().nth(1).unwrap(),
-        self.horizontal_word,
-        self.left_word.chars().nth(3).unwrap(), self.vertical_word.chars().nth(3).unwrap(), self.right_word.chars().nth(3).unwrap(),
-        self.bottom_word)
-    }
-}
-impl fmt::Display for Waffle2 {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}\n\
-            {} {} {} {}\n\
-            {}\n\
-            {} {} {} {}\n\
-            {}\n\
-            {} {} {} {}\n\
-            {}",
-            self.words[0],
-            self.words[1].chars().nth(1).unwrap(), self.words[3].chars().nth(1).unwrap(), self.words[5].chars().nth(1).unwrap(), self.words[7].chars().nth(1).unwrap(),
-            self.words[2],
-            self.words[1].chars().nth(3).unwrap(), self.words[3].chars().nth(3).unwrap(), self.words[5].chars().nth(3).unwrap(), self.words[7].chars().nth(3).unwrap(),
-            self.words[4],
-            self.words[1].chars().nth(5).unwrap(), self.words[3].chars().nth(5).unwrap(), self.words[5].chars().nth(5).unwrap(), self.words[7].chars().nth(5).unwrap(),
-            self.words[6],
-        )
+        view! {
+            <div>
+                <select
+                    on:change=move |ev| set_size.set(event_target_value(&ev).parse().unwrap_or(SIZES[0]))
+                    prop:disabled=move || progress.get().is_some()
+                >
+                    {SIZES.iter().map(|s| view! {
+                        <option value=s.to_string() selected=*s == size.get_untracked()>
+                            {format!("{}x{}", s, s)}
+                        </option>
+                    }).collect_view()}
+                </select>
+                {move || match progress.get() {
+                    None => view! { <button on:click=start>"New waffle"</button> }.into_view(),
+                    Some(nodes) => view! { <progress /> " " {format!("{} nodes", nodes)} }.into_view(),
+                }}
+                {move || error.get().map(|e| view! { <div>"couldn't generate a waffle: " {e}</div> })}
+            </div>
+            {move || waffle.get().map(|w| view! { <WaffleSwapComponent initial_waffle=w/> })}
+        }
     }
 }
+
 #[cfg(test)]
 mod test {
-    use std::result::Result;
+    use super::{generate_waffle, WaffleSwap};
+    use crate::board::Board;
     use crate::dictionary::Dictionary;
-
-    use super::{Waffle, Waffle2};
+    use crate::grid::Grid;
+    use crate::solver::{Budget, SearchOptions};
 
     #[test]
-    fn gen_waffle() -> Result<(), ()> {
-        let dictionary = Dictionary::new().map_err(|_e| ())?;
-
-        let waffle = Waffle::generate(dictionary)?;
-
-        println!("{}", waffle);
-
-        Ok(())
+    fn waffle_layouts() {
+        for (size, words) in [(5, 6), (7, 8)] {
+            let waffle = Grid::new_waffle(size);
+            let slots = waffle.slots();
+            assert_eq!(slots.len(), words);
+            assert!(slots.iter().all(|slot| slot.cells.len() == size));
+            assert!(!waffle.has_isles());
+        }
     }
 
     #[test]
-    fn gen_waffle2() -> Result<(), ()> {
-        let dictionary = Dictionary::new().map_err(|_e| ())?;
-
-        let res = Waffle2::generate(&dictionary);
-
-        match res {
-            Some(waffle) => {
-                println!("{:?}", waffle);
-                println!("{}", waffle);
-            },
-            None => println!("no waffle found")
+    fn play_a_waffle() {
+        let words = std::fs::read_to_string("dictionaries/english/most_frequent_words").unwrap();
+        let dictionary = Dictionary::from_words(words.lines().map(|w| w.to_string()).collect());
+        let options = SearchOptions {
+            seed: Some(3),
+            ..Default::default()
         };
+        let grid = generate_waffle(5, &dictionary, Budget::default(), &options)
+            .0
+            .solved()
+            .unwrap();
+        for word in grid.slot_words() {
+            assert!(dictionary.common_words.contains(&word));
+        }
 
-        Ok(())
+        let swap = WaffleSwap::from(grid.clone());
+        println!("{}", swap);
+        // 21 letters, the corners and the centre stay put
+        assert_eq!(swap.get_swappable_cells().len(), 16);
+        assert!(WaffleSwap::from_layouts(grid.clone(), grid, vec![])
+            .unwrap()
+            .is_solved());
     }
-
-    
-}
\ No newline at end of file
+}
@@ -0,0 +1,464 @@
+use std::fmt;
+
+use rand::{rngs::StdRng, seq::SliceRandom, thread_rng, Rng, SeedableRng};
+
+use crate::board::{Board, Cell, Kind, Line};
+use crate::dictionary::{Dictionary, WordSet};
+use crate::grid_beehive::GridBeehive;
+
+// shorter runs of filler spell words too often to be worth avoiding
+pub const MIN_ACCIDENTAL_LEN: usize = 4;
+// fresh tries at placing the whole list before settling for the best one
+const ATTEMPTS: usize = 50;
+
+// a theme word and the cells it is spelled on, first letter first
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placement {
+    pub word: String,
+    pub cells: Vec<Cell>,
+}
+
+// the hidden words read along any axis, one way or the other
+#[derive(Debug, Clone)]
+pub struct WordSearch {
+    board: GridBeehive,
+    placements: Vec<Placement>,
+}
+
+impl WordSearch {
+    pub fn board(&self) -> &GridBeehive {
+        &self.board
+    }
+    pub fn placements(&self) -> &Vec<Placement> {
+        &self.placements
+    }
+
+    // the cells from one to the other along an axis, both ends included
+    pub fn path(&self, from: &Cell, to: &Cell) -> Option<Vec<Cell>> {
+        if from == to {
+            return Some(vec![*from]);
+        }
+        self.board.axes().kinds().into_iter().find_map(|kind| {
+            if kind.index_of(from.hex()) != kind.index_of(to.hex()) {
+                return None;
+            }
+            let cells = line_through(&self.board, &kind, from);
+            let (a, b) = (
+                cells.iter().position(|c| c == from)?,
+                cells.iter().position(|c| c == to)?,
+            );
+            let mut path: Vec<Cell> = cells[a.min(b)..=a.max(b)].to_vec();
+            if a > b {
+                path.reverse();
+            }
+            Some(path)
+        })
+    }
+
+    // the placement marked by dragging from one end to the other, either way round
+    pub fn find(&self, from: &Cell, to: &Cell) -> Option<usize> {
+        let path = self.path(from, to)?;
+        let reversed: Vec<Cell> = path.iter().rev().cloned().collect();
+        self.placements
+            .iter()
+            .position(|p| p.cells == path || p.cells == reversed)
+    }
+
+    // words of the dictionary the filler spells by chance, the theme words and
+    // the words inside them don't count
+    pub fn accidental_words(&self, words: &WordSet) -> Vec<String> {
+        let mut found = vec![];
+        for line in self.board.lines() {
+            for run in open_runs(&self.board, &line) {
+                for start in 0..run.len() {
+                    for end in (start + MIN_ACCIDENTAL_LEN)..=run.len() {
+                        let cells = &run[start..end];
+                        if self.within_placement(cells) {
+                            continue;
+                        }
+                        let word: String = cells.iter().map(|c| self.letter(c)).collect();
+                        let backwards: String = word.chars().rev().collect();
+                        found.extend([word, backwards].into_iter().filter(|w| words.contains(w)));
+                    }
+                }
+            }
+        }
+        found.sort();
+        found.dedup();
+
+        found
+    }
+
+    fn letter(&self, cell: &Cell) -> char {
+        *self.board.get_cell(cell.row(), cell.col()).unwrap()
+    }
+    fn within_placement(&self, cells: &[Cell]) -> bool {
+        self.placements
+            .iter()
+            .any(|p| cells.iter().all(|c| p.cells.contains(c)))
+    }
+}
+
+// all the cells of the line of that kind going through a cell
+fn line_through(board: &GridBeehive, kind: &Kind, cell: &Cell) -> Vec<Cell> {
+    let index = kind.index_of(cell.hex()) as usize;
+    board.line_cells(&Line::new(*kind, index))
+}
+
+// the stretches of a line between blocks
+fn open_runs(board: &GridBeehive, line: &Line) -> Vec<Vec<Cell>> {
+    board
+        .line_cells(line)
+        .split(|c| board.get_cell(c.row(), c.col()) == Some(&'_'))
+        .filter(|run| !run.is_empty())
+        .map(|run| run.to_vec())
+        .collect()
+}
+
+// themes are read from a text file, one `theme = word, word, ...` per line
+pub fn parse_themes(text: &str) -> Result<Vec<(String, Vec<String>)>, String> {
+    let mut themes = vec![];
+    for (i, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        let (name, words) = line
+            .split_once('=')
+            .ok_or(format!("line {}: expected `theme = word, word`", i + 1))?;
+        let words = parse_words(words).map_err(|e| format!("line {}: {}", i + 1, e))?;
+        themes.push((name.trim().to_string(), words));
+    }
+
+    Ok(themes)
+}
+
+// a comma separated list of words, the same check for a theme and for words
+// given by hand
+pub fn parse_words(text: &str) -> Result<Vec<String>, String> {
+    text.split(',')
+        .map(|w| w.trim().to_lowercase())
+        .map(
+            |w| match w.is_empty() || !w.chars().all(|c| c.is_ascii_lowercase()) {
+                true => Err(format!("{:?} isn't a word", w)),
+                false => Ok(w),
+            },
+        )
+        .collect()
+}
+
+// longest words go in first, each at a random spot that agrees with the letters
+// already there. the rest is filled a cell at a time with letters that don't
+// complete a dictionary word
+pub fn generate_word_search(
+    shape: &GridBeehive,
+    words: &[String],
+    dictionary: &Dictionary,
+    seed: Option<u64>,
+) -> Result<WordSearch, String> {
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_rng(thread_rng()).unwrap(),
+    };
+    let known = WordSet::from_words(dictionary.all_words.iter().map(|w| w.as_str()));
+    let mut words: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+    words.sort_by_key(|w| std::cmp::Reverse(w.len()));
+    let longest_run = shape
+        .lines()
+        .iter()
+        .flat_map(|line| open_runs(shape, line))
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0);
+    if let Some(w) = words
+        .iter()
+        .find(|w| w.is_empty() || w.chars().count() > longest_run)
+    {
+        return Err(format!(
+            "{:?} doesn't fit, words take 1 to {} letters here",
+            w, longest_run
+        ));
+    }
+
+    let mut best: Option<(usize, WordSearch)> = None;
+    for _ in 0..ATTEMPTS {
+        let Some(mut search) = place_words(shape, &words, &mut rng) else {
+            continue;
+        };
+        fill(&mut search, &known, &mut rng);
+        let accidental = search.accidental_words(&known).len();
+        if accidental == 0 {
+            return Ok(search);
+        }
+        if best.as_ref().is_none_or(|(cnt, _)| accidental < *cnt) {
+            best = Some((accidental, search));
+        }
+    }
+
+    best.map(|(_, search)| search)
+        .ok_or(format!("couldn't fit {} on the board", words.join(", ")))
+}
+
+fn place_words(shape: &GridBeehive, words: &[String], rng: &mut StdRng) -> Option<WordSearch> {
+    let runs: Vec<Vec<Cell>> = shape
+        .lines()
+        .iter()
+        .flat_map(|line| open_runs(shape, line))
+        .collect();
+    let mut search = WordSearch {
+        board: shape.clone(),
+        placements: vec![],
+    };
+
+    for word in words {
+        let len = word.chars().count();
+        let mut spots: Vec<Vec<Cell>> = runs
+            .iter()
+            .flat_map(|run| run.windows(len))
+            .flat_map(|cells| [cells.to_vec(), cells.iter().rev().cloned().collect()])
+            .collect();
+        spots.shuffle(rng);
+
+        let spot = spots.into_iter().find(|cells| {
+            cells
+                .iter()
+                .zip(word.chars())
+                .all(|(c, l)| ['\0', l].contains(&search.letter(c)))
+        })?;
+        for (cell, letter) in spot.iter().zip(word.chars()) {
+            search.board.set_cell(cell.row(), cell.col(), letter);
+        }
+        search.placements.push(Placement {
+            word: word.clone(),
+            cells: spot,
+        });
+    }
+
+    Some(search)
+}
+
+fn fill(search: &mut WordSearch, known: &WordSet, rng: &mut StdRng) {
+    let mut empty: Vec<Cell> = (0..search.board.rows())
+        .flat_map(|r| (0..search.board.cols()).map(move |c| Cell::new(r, c)))
+        .filter(|c| search.letter(c) == '\0')
+        .collect();
+    empty.shuffle(rng);
+
+    for cell in empty {
+        let mut letters: Vec<char> = ('a'..='z').collect();
+        letters.shuffle(rng);
+        // when every letter spells something a random one stays
+        let fallback = letters[rng.gen_range(0..letters.len())];
+        let letter = letters
+            .into_iter()
+            .find(|l| {
+                search.board.set_cell(cell.row(), cell.col(), *l);
+                !spells_word(search, &cell, known)
+            })
+            .unwrap_or(fallback);
+        search.board.set_cell(cell.row(), cell.col(), letter);
+    }
+}
+
+// whether a filled stretch through the cell reads as a word, either way
+fn spells_word(search: &WordSearch, cell: &Cell, known: &WordSet) -> bool {
+    search.board.axes().kinds().iter().any(|kind| {
+        let cells = line_through(&search.board, kind, cell);
+        let at = cells.iter().position(|c| c == cell).unwrap();
+        let letters: Vec<char> = cells.iter().map(|c| search.letter(c)).collect();
+        let open = |l: &char| *l != '_' && *l != '\0';
+        let first = (0..at).rev().take_while(|i| open(&letters[*i])).last();
+        let last = (at + 1..letters.len())
+            .take_while(|i| open(&letters[*i]))
+            .last();
+        let (first, last) = (first.unwrap_or(at), last.unwrap_or(at));
+
+        (first..=at).any(|start| {
+            (at.max(start + MIN_ACCIDENTAL_LEN - 1)..=last).any(|end| {
+                if search.within_placement(&cells[start..=end]) {
+                    return false;
+                }
+                let word: String = letters[start..=end].iter().collect();
+                let backwards: String = word.chars().rev().collect();
+                known.contains(&word) || known.contains(&backwards)
+            })
+        })
+    })
+}
+
+impl fmt::Display for WordSearch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (r, row) in self.board.to_rows().iter().enumerate() {
+            let cells: Vec<String> = row
+                .chars()
+                .map(|c| match c {
+                    '_' => " ".to_string(),
+                    l => l.to_uppercase().to_string(),
+                })
+                .collect();
+            writeln!(f, "{}{}", " ".repeat(r), cells.join(" ").trim_end())?;
+        }
+        let words: Vec<&str> = self.placements.iter().map(|p| p.word.as_str()).collect();
+        writeln!(f, "{}", words.join(", "))
+    }
+}
+
+// a random theme on a hexagon, filled against the common words served next to
+// the app
+pub async fn fetch_word_search(_c: ()) -> Result<WordSearch, String> {
+    let fetch = |url: &'static str| async move {
+        reqwasm::http::Request::get(url)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .text()
+            .await
+            .map_err(|e| e.to_string())
+    };
+    let themes = parse_themes(&fetch("/assets/themes.txt").await?)?;
+    let words = fetch("/most_frequent_words").await?;
+    let dictionary = Dictionary::from_words(words.lines().map(|w| w.to_string()).collect());
+
+    let (_, theme) = themes
+        .choose(&mut thread_rng())
+        .ok_or("no themes to pick from")?;
+    generate_word_search(&GridBeehive::new_hexagon(4), theme, &dictionary, None)
+}
+
+pub mod ui {
+    use leptos::*;
+
+    use super::*;
+
+    // press on the first letter of a word and let go on the last one
+    #[component]
+    pub fn WordSearchComponent(search: WordSearch) -> impl IntoView {
+        let (rows, cols) = (search.board().rows(), search.board().cols());
+        let search = store_value(search);
+        let (found, set_found) = create_signal::<Vec<usize>>(vec![]);
+        let (drag, set_drag) = create_signal::<Option<(Cell, Cell)>>(None);
+
+        let marking = move |cell: &Cell| {
+            drag.get()
+                .and_then(|(from, to)| search.with_value(|s| s.path(&from, &to)))
+                .is_some_and(|path| path.contains(cell))
+        };
+        let is_found = move |cell: &Cell| {
+            search.with_value(|s| {
+                found.with(|f| f.iter().any(|i| s.placements()[*i].cells.contains(cell)))
+            })
+        };
+        let release = move || {
+            if let Some((from, to)) = drag.get() {
+                if let Some(i) = search.with_value(|s| s.find(&from, &to)) {
+                    if !found.with(|f| f.contains(&i)) {
+                        set_found.update(|f| f.push(i));
+                    }
+                }
+            }
+            set_drag.set(None);
+        };
+        let won = move || found.with(|f| f.len()) == search.with_value(|s| s.placements().len());
+
+        view! {
+            <div
+                class="beehive-container word-search"
+                style:width = format!("{}em", 2 * cols + rows - 1)
+                style:grid-template-columns = format!("repeat({}, minmax(0, 1fr))", 2 * cols + rows - 1)
+                on:mouseup=move |_| release()
+                on:mouseleave=move |_| set_drag.set(None)
+            >
+                {(0..rows).map(|r| {
+                    let offsets_before = (0..r).map(|_| view! { <div class="offset" /> }).collect_view();
+                    let cells = (0..cols).map(|c| {
+                        let cell = Cell::new(r, c);
+                        match search.with_value(|s| s.letter(&cell)) {
+                            '_' => view! { <div class="empty-cell" /> },
+                            letter => view! {
+                                <div
+                                    class="cell"
+                                    class:is-marking = move || marking(&cell)
+                                    class:is-found = move || is_found(&cell)
+                                    on:mousedown=move |ev| {
+                                        ev.prevent_default();
+                                        set_drag.set(Some((cell, cell)));
+                                    }
+                                    on:mouseenter=move |_| {
+                                        if let Some((from, _)) = drag.get() {
+                                            set_drag.set(Some((from, cell)));
+                                        }
+                                    }
+                                >
+                                    {letter.to_uppercase().to_string()}
+                                </div>
+                            },
+                        }
+                    }).collect_view();
+                    let offsets_after = (0..(rows - r - 1)).map(|_| view! { <div class="offset" /> }).collect_view();
+
+                    vec![offsets_before, cells, offsets_after].into_iter().collect_view()
+                }).collect_view()}
+            </div>
+            <ul class="search-words">
+                {search.with_value(|s| s.placements().clone()).into_iter().enumerate().map(|(i, p)| view! {
+                    <li class:is-found = move || found.with(|f| f.contains(&i))>{p.word}</li>
+                }).collect_view()}
+            </ul>
+            {move || won().then(|| view! { <div>"All words found!"</div> })}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{generate_word_search, parse_themes, parse_words};
+    use crate::board::{Board, Cell};
+    use crate::dictionary::{Dictionary, WordSet};
+    use crate::grid_beehive::GridBeehive;
+
+    #[test]
+    fn themed_word_search() {
+        let words = std::fs::read_to_string("dictionaries/english/most_frequent_words").unwrap();
+        let dictionary = Dictionary::from_words(words.lines().map(|w| w.to_string()).collect());
+        let themes = parse_themes(&std::fs::read_to_string("assets/themes.txt").unwrap()).unwrap();
+        let (_, theme) = themes.iter().find(|(name, _)| name == "space").unwrap();
+
+        let shape = GridBeehive::new_hexagon(4);
+        let search = generate_word_search(&shape, theme, &dictionary, Some(7)).unwrap();
+        println!("{}", search);
+        assert_eq!(search.placements().len(), theme.len());
+        for placement in search.placements() {
+            let read: String = placement
+                .cells
+                .iter()
+                .map(|c| *search.board().get_cell(c.row(), c.col()).unwrap())
+                .collect();
+            assert_eq!(read, placement.word);
+
+            // marked from either end
+            let (first, last) = (placement.cells[0], *placement.cells.last().unwrap());
+            let i = search.find(&first, &last).unwrap();
+            assert_eq!(search.placements()[i].word, placement.word);
+            assert_eq!(search.find(&last, &first), Some(i));
+        }
+
+        // the shape is kept and every open cell gets a letter
+        for (filled, empty) in search.board().to_rows().iter().zip(shape.to_rows()) {
+            for (f, e) in filled.chars().zip(empty.chars()) {
+                assert_eq!(f == '_', e == '_');
+                assert!(f == '_' || f.is_ascii_lowercase());
+            }
+        }
+        let known = WordSet::from_words(dictionary.all_words.iter().map(|w| w.as_str()));
+        assert!(search.accidental_words(&known).is_empty());
+
+        assert!(search.path(&Cell::new(4, 0), &Cell::new(6, 1)).is_none());
+        assert!(parse_themes("pets = cat, d0g").is_err());
+        assert!(parse_words("cat,dog,").is_err());
+        assert_eq!(parse_words("CAT, dog").unwrap(), vec!["cat", "dog"]);
+        let words = vec!["".to_string()];
+        assert!(generate_word_search(&shape, &words, &dictionary, None).is_err());
+        let words = vec!["abcdefghij".to_string()];
+        assert!(generate_word_search(&shape, &words, &dictionary, None).is_err());
+    }
+}
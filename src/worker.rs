@@ -0,0 +1,320 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    Blob, BlobPropertyBag, DedicatedWorkerGlobalScope, HtmlLinkElement, MessageEvent, Response,
+    Url, Worker, WorkerOptions, WorkerType,
+};
+
+use crate::beehive_serde::BeehiveSerde;
+use crate::board::Board;
+use crate::dictionary::Dictionary;
+use crate::grid_beehive::GridBeehive;
+use crate::solver::{Budget, Outcome, Search, SearchOptions};
+
+// nodes between two progress reports
+const CHUNK_NODES: usize = 2000;
+// served next to the app, see index.html
+const WORDS_FILE: &str = "most_frequent_words";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerateRequest {
+    pub layout: BeehiveSerde,
+    pub allow_adding_blacks: bool,
+    pub seed: u64,
+    pub words_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum WorkerMessage {
+    Progress { nodes: usize, elapsed_ms: f64 },
+    Done(BeehiveSerde),
+    Failed(String),
+}
+
+thread_local! {
+    static DICTIONARY: RefCell<Option<Rc<Dictionary>>> = const { RefCell::new(None) };
+}
+
+// the app and the worker run the same wasm, only the worker has no window
+pub fn is_worker() -> bool {
+    web_sys::window().is_none()
+}
+
+// worker side: answers every request with progress reports and a beehive
+pub fn run_worker() {
+    let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+
+    let onmessage = Closure::<dyn Fn(MessageEvent)>::new(move |event: MessageEvent| {
+        let request = event
+            .data()
+            .as_string()
+            .and_then(|data| serde_json::from_str::<GenerateRequest>(&data).ok());
+        leptos::spawn_local(async move {
+            let message = match request {
+                Some(request) => generate(request).await,
+                None => WorkerMessage::Failed("unreadable request".to_string()),
+            };
+            post(&message);
+        });
+    });
+    scope.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+    onmessage.forget();
+}
+
+async fn generate(request: GenerateRequest) -> WorkerMessage {
+    let dictionary = match load_dictionary(&request.words_url).await {
+        Ok(dictionary) => dictionary,
+        Err(e) => return WorkerMessage::Failed(e),
+    };
+
+    let layout: GridBeehive = request.layout.into();
+    let options = SearchOptions {
+        seed: Some(request.seed),
+        ..Default::default()
+    };
+    let mut search = layout.search(
+        &dictionary,
+        request.allow_adding_blacks,
+        Budget::default(),
+        &options,
+    );
+    loop {
+        match search.step(CHUNK_NODES) {
+            None => {
+                let stats = search.stats();
+                post(&WorkerMessage::Progress {
+                    nodes: stats.nodes,
+                    elapsed_ms: stats.elapsed.as_secs_f64() * 1000.0,
+                });
+            }
+            Some(Outcome::Solved(grid)) => return WorkerMessage::Done(grid.into()),
+            Some(_) => return WorkerMessage::Failed("this layout can't be filled".to_string()),
+        }
+    }
+}
+
+async fn load_dictionary(url: &str) -> Result<Rc<Dictionary>, String> {
+    if let Some(dictionary) = DICTIONARY.with(|d| d.borrow().clone()) {
+        return Ok(dictionary);
+    }
+
+    let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+    let fetch_failed = |_e: JsValue| format!("can't fetch the words at {}", url);
+    let response: Response = JsFuture::from(scope.fetch_with_str(url))
+        .await
+        .map_err(fetch_failed)?
+        .unchecked_into();
+    let text = JsFuture::from(response.text().map_err(fetch_failed)?)
+        .await
+        .map_err(fetch_failed)?
+        .as_string()
+        .unwrap_or_default();
+
+    let dictionary = Rc::new(Dictionary::from_words(
+        text.lines().map(|w| w.to_string()).collect(),
+    ));
+    DICTIONARY.with(|d| *d.borrow_mut() = Some(dictionary.clone()));
+
+    Ok(dictionary)
+}
+
+fn post(message: &WorkerMessage) {
+    let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+    let data = serde_json::to_string(message).unwrap();
+    let _ = scope.post_message(&JsValue::from_str(&data));
+}
+
+// app side handle, dropping it or calling cancel stops the worker
+pub struct GenerationWorker {
+    worker: Worker,
+    _onmessage: Closure<dyn Fn(MessageEvent)>,
+}
+
+impl GenerationWorker {
+    pub fn spawn(
+        layout: impl Into<BeehiveSerde>,
+        allow_adding_blacks: bool,
+        seed: u64,
+        on_message: impl Fn(WorkerMessage) + 'static,
+    ) -> Result<Self, String> {
+        let document = leptos::document();
+        // trunk preloads the js glue and the wasm, the worker loads them again
+        let href = |selector: &str| -> Result<String, String> {
+            document
+                .query_selector(selector)
+                .ok()
+                .flatten()
+                .and_then(|e| e.dyn_into::<HtmlLinkElement>().ok())
+                .map(|link| link.href())
+                .ok_or(format!("no {} in the page", selector))
+        };
+        let glue = href("link[rel=modulepreload]")?;
+        let wasm = href("link[type='application/wasm']")?;
+
+        let script = format!("import init from \"{}\";\ninit(\"{}\");\n", glue, wasm);
+        let blob = Blob::new_with_str_sequence_and_options(
+            &js_sys::Array::of1(&JsValue::from_str(&script)),
+            BlobPropertyBag::new().type_("text/javascript"),
+        )
+        .map_err(|_| "can't create the worker script")?;
+        let url = Url::create_object_url_with_blob(&blob)
+            .map_err(|_| "can't create the worker script")?;
+
+        let worker = Worker::new_with_options(&url, WorkerOptions::new().type_(WorkerType::Module))
+            .map_err(|_| "can't start a worker")?;
+        let onmessage = Closure::<dyn Fn(MessageEvent)>::new(move |event: MessageEvent| {
+            let message = event
+                .data()
+                .as_string()
+                .and_then(|data| serde_json::from_str::<WorkerMessage>(&data).ok())
+                .unwrap_or(WorkerMessage::Failed("unreadable answer".to_string()));
+            on_message(message);
+        });
+        worker.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+
+        let base = document.base_uri().ok().flatten().unwrap_or_default();
+        let words_url = Url::new_with_base(WORDS_FILE, &base)
+            .map(|u| u.href())
+            .unwrap_or(WORDS_FILE.to_string());
+        let request = GenerateRequest {
+            layout: layout.into(),
+            allow_adding_blacks,
+            seed,
+            words_url,
+        };
+        worker
+            .post_message(&JsValue::from_str(
+                &serde_json::to_string(&request).unwrap(),
+            ))
+            .map_err(|_| "can't reach the worker")?;
+
+        Ok(Self {
+            worker,
+            _onmessage: onmessage,
+        })
+    }
+
+    pub fn cancel(&self) {
+        self.worker.terminate();
+    }
+}
+
+impl Drop for GenerationWorker {
+    fn drop(&mut self) {
+        self.worker.terminate();
+    }
+}
+
+pub mod ui {
+    use leptos::*;
+    use rand::random;
+
+    use super::*;
+
+    // sizes that fill in a few seconds at most
+    const SIZES: [(usize, usize); 4] = [(4, 4), (5, 5), (5, 6), (7, 7)];
+
+    #[component]
+    pub fn GenerateComponent(on_generated: WriteSignal<Option<BeehiveSerde>>) -> impl IntoView {
+        let (size, set_size) = create_signal(1);
+        let (progress, set_progress) = create_signal::<Option<(usize, f64)>>(None);
+        let (error, set_error) = create_signal::<Option<String>>(None);
+        let worker = store_value::<Option<GenerationWorker>>(None);
+
+        let start = move |_| {
+            let (rows, cols) = SIZES[size.get()];
+            let (layout, allow_adding_blacks) = GridBeehive::layout(rows, cols);
+            set_error.set(None);
+            set_progress.set(Some((0, 0.0)));
+
+            let spawned =
+                GenerationWorker::spawn(layout, allow_adding_blacks, random(), move |message| {
+                    match message {
+                        WorkerMessage::Progress { nodes, elapsed_ms } => {
+                            set_progress.set(Some((nodes, elapsed_ms)))
+                        }
+                        // the handle is dropped by the next run, this closure belongs to it
+                        WorkerMessage::Done(beehive) => {
+                            set_progress.set(None);
+                            worker.with_value(|w| w.as_ref().map(|w| w.cancel()));
+                            on_generated.set(Some(beehive));
+                        }
+                        WorkerMessage::Failed(e) => {
+                            set_progress.set(None);
+                            worker.with_value(|w| w.as_ref().map(|w| w.cancel()));
+                            set_error.set(Some(e));
+                        }
+                    }
+                });
+            match spawned {
+                Ok(w) => worker.set_value(Some(w)),
+                Err(e) => {
+                    set_progress.set(None);
+                    set_error.set(Some(e));
+                }
+            }
+        };
+        let cancel = move |_| {
+            worker.set_value(None);
+            set_progress.set(None);
+        };
+
+        view! {
+            <div>
+                <select
+                    on:change=move |ev| set_size.set(event_target_value(&ev).parse().unwrap_or(1))
+                    prop:disabled=move || progress.get().is_some()
+                >
+                    {SIZES.iter().enumerate().map(|(i, (rows, cols))| view! {
+                        <option value=i.to_string() selected=i == size.get_untracked()>
+                            {format!("{}x{}", rows, cols)}
+                        </option>
+                    }).collect_view()}
+                </select>
+                {move || match progress.get() {
+                    None => view! { <button on:click=start>"New beehive"</button> }.into_view(),
+                    Some((nodes, elapsed_ms)) => view! {
+                        <progress />
+                        " " {format!("{} nodes, {:.1}s", nodes, elapsed_ms / 1000.0)} " "
+                        <button on:click=cancel>"Cancel"</button>
+                    }.into_view(),
+                }}
+                {move || error.get().map(|e| view! { <div>"couldn't generate a beehive: " {e}</div> })}
+            </div>
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{GenerateRequest, WorkerMessage};
+    use crate::grid_beehive::GridBeehive;
+
+    #[test]
+    fn messages_round_trip() {
+        // open cells are '\0', json has to carry them both ways
+        let request = GenerateRequest {
+            layout: GridBeehive::new_5x5_honeycomb().into(),
+            allow_adding_blacks: false,
+            seed: 42,
+            words_url: "http://localhost:8080/most_frequent_words".to_string(),
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        let decoded: GenerateRequest = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.layout.layout(), request.layout.layout());
+
+        let json = serde_json::to_string(&WorkerMessage::Progress {
+            nodes: 2000,
+            elapsed_ms: 12.5,
+        })
+        .unwrap();
+        match serde_json::from_str(&json).unwrap() {
+            WorkerMessage::Progress { nodes, .. } => assert_eq!(nodes, 2000),
+            _ => panic!("progress decoded as another message"),
+        }
+    }
+}